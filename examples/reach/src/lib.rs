@@ -4,44 +4,211 @@ mod sylvan_io;
 
 use std::error::Error;
 
-/// Performs state space exploration of the given model and returns the number of states.
-pub fn run(config: &Config) -> Result<usize, Box<dyn Error>>
+/// Performs state space exploration of the given model and returns the number
+/// of states, or None if the number of states does not fit in a u128.
+pub fn run(config: &Config) -> Result<Option<u128>, Box<dyn Error>>
 {
     // Initialize the library.
     let mut storage = ldd::Storage::new();
-    storage.enable_performance_metrics(true);
+    storage.enable_performance_metrics(config.verbose);
 
-    let (initial_state, transitions) = sylvan_io::load_model(&mut storage, &config.filename)?;
+    let (mut initial_state, mut transitions) = sylvan_io::load_model(&mut storage, &config.filename)?;
 
-    let mut todo = initial_state.clone();
-    let mut states = initial_state; // The state space.
-    let mut iteration = 0;
+    // The variable order that is used, where order[i] is the variable of the model at position i.
+    let mut order: Vec<usize> = (0..ldd::height(&storage, &initial_state) as usize).collect();
 
-    while todo != *storage.empty_set()
+    if config.force
     {
-        let mut todo1 = storage.empty_set().clone();
-        for transition in transitions.iter()
+        // Every group depends on the positions that it reads or writes.
+        let groups: Vec<Vec<usize>> = transitions.iter()
+            .map(|transition| transition.read_proj.iter().chain(&transition.write_proj).map(|x| *x as usize).collect())
+            .collect();
+
+        let before = total_node_count(&storage, &initial_state, &transitions);
+        let permutation = ldd::force(order.len(), &groups);
+        let mut forced_transitions = transitions.clone();
+        let forced_initial_state = apply_permutation(&mut storage, &initial_state, &mut forced_transitions, &permutation);
+        let after = total_node_count(&storage, &forced_initial_state, &forced_transitions);
+
+        // The heuristic does not consider the number of nodes, so the original order is kept when it is smaller.
+        if after <= before
         {
-            let result = ldd::relational_product(&mut storage, &todo, &transition.relation, &transition.meta);
-            todo1 = ldd::union(&mut storage, &todo1, &result);
+            initial_state = forced_initial_state;
+            transitions = forced_transitions;
+            order.clone_from(&permutation);
         }
 
-        todo = ldd::minus(&mut storage, &todo1, &states);
-        states = ldd::union(&mut storage, &states, &todo);
+        if config.verbose
+        {
+            if after <= before
+            {
+                eprintln!("static ordering with {:?} changed the number of nodes from {} to {}", permutation, before, after);
+            }
+            else
+            {
+                eprintln!("static ordering with {:?} would increase the number of nodes from {} to {}, keeping the original order", permutation, before, after);
+            }
+        }
+    }
+
+    if config.reorder
+    {
+        let relations: Vec<(ldd::Ldd, ldd::Ldd)> = transitions.iter()
+            .map(|transition| (transition.relation.clone(), transition.meta.clone()))
+            .collect();
 
-        eprintln!("iteration {}", iteration);
-        iteration += 1;
+        let before = total_node_count(&storage, &initial_state, &transitions);
+        let permutation = ldd::sifting(&mut storage, &[initial_state.clone()], &relations);
+        initial_state = apply_permutation(&mut storage, &initial_state, &mut transitions, &permutation);
+
+        // The permutation is relative to the current order, so it is composed to obtain the order of the variables of the model.
+        order = permutation.iter().map(|position| order[*position]).collect();
+        eprintln!("reordering with {:?} reduced the number of nodes from {} to {}", order, before, total_node_count(&storage, &initial_state, &transitions));
     }
 
-    let num_of_states = ldd::len(&mut storage, &states);
-    println!("The model has {} states", num_of_states);
+    let states = match config.strategy {
+        Strategy::Bfs => {
+            let mut todo = initial_state.clone();
+            let mut states = initial_state; // The state space.
+            let mut iteration = 0;
+
+            while todo != *storage.empty_set()
+            {
+                let mut todo1 = storage.empty_set().clone();
+                for transition in transitions.iter()
+                {
+                    let result = ldd::relational_product(&mut storage, &todo, &transition.relation, &transition.meta);
+                    todo1 = ldd::union(&mut storage, &todo1, &result);
+                }
+
+                (states, todo) = ldd::zip(&mut storage, &states, &todo1);
+
+                if config.verbose
+                {
+                    eprintln!("iteration {} uses {} nodes", iteration, ldd::node_count(&storage, &[states.clone(), todo.clone()]));
+                }
+                iteration += 1;
+            }
+
+            states
+        }
+        Strategy::Chaining => {
+            let mut todo = initial_state.clone();
+            let mut states = initial_state; // The state space.
+            let mut iteration = 0;
+
+            while todo != *storage.empty_set()
+            {
+                // Every transition group is applied to the frontier extended by the previous groups.
+                let visited = states.clone();
+                for transition in transitions.iter()
+                {
+                    let successors = ldd::relational_product(&mut storage, &todo, &transition.relation, &transition.meta);
+
+                    let new_states;
+                    (states, new_states) = ldd::zip(&mut storage, &states, &successors);
+                    todo = ldd::union(&mut storage, &todo, &new_states);
+                }
+
+                todo = ldd::minus(&mut storage, &states, &visited);
+
+                if config.verbose
+                {
+                    eprintln!("iteration {} uses {} nodes", iteration, ldd::node_count(&storage, &[states.clone(), todo.clone()]));
+                }
+                iteration += 1;
+            }
+
+            states
+        }
+        Strategy::Saturation => {
+            let groups: Vec<(ldd::Ldd, ldd::Ldd)> = transitions.iter()
+                .map(|transition| (transition.relation.clone(), transition.meta.clone()))
+                .collect();
+
+            ldd::saturation(&mut storage, &initial_state, &groups)
+        }
+    };
+
+    // Only print the exact number of states when it fits in a u128.
+    let num_of_states = ldd::len_u128(&mut storage, &states);
+    match num_of_states
+    {
+        Some(num_of_states) => println!("The model has {} states", num_of_states),
+        None => println!("The model has approximately 2^{:.2} states", ldd::log2_len(&mut storage, &states)),
+    }
 
     Ok(num_of_states)
 }
 
+/// Permutes the transitions as defined by ldd::permute and returns the permuted initial state.
+fn apply_permutation(storage: &mut ldd::Storage, initial_state: &ldd::Ldd, transitions: &mut [sylvan_io::Transition], permutation: &[usize]) -> ldd::Ldd
+{
+    // The new position of every position.
+    let mut inverse = vec![0; permutation.len()];
+    for (i, position) in permutation.iter().enumerate()
+    {
+        inverse[*position] = i as ldd::Value;
+    }
+
+    for transition in transitions.iter_mut()
+    {
+        (transition.relation, transition.meta) = ldd::permute_relation(storage, &transition.relation, &transition.meta, permutation);
+
+        for proj in [&mut transition.read_proj, &mut transition.write_proj]
+        {
+            for position in proj.iter_mut()
+            {
+                *position = inverse[*position as usize];
+            }
+            proj.sort_unstable();
+        }
+    }
+
+    ldd::permute(storage, initial_state, permutation)
+}
+
+/// Returns the number of nodes of the initial state and the relations of the transitions.
+fn total_node_count(storage: &ldd::Storage, initial_state: &ldd::Ldd, transitions: &[sylvan_io::Transition]) -> usize
+{
+    let mut roots: Vec<ldd::Ldd> = transitions.iter().map(|transition| transition.relation.clone()).collect();
+    roots.push(initial_state.clone());
+    ldd::node_count(storage, &roots)
+}
+
+/// The help text that describes the arguments of the tool.
+pub const USAGE: &str = "Usage: reach <model.ldd> [options]
+
+Options:
+  --strategy <bfs|chaining|saturation>  The exploration strategy, bfs by default.
+  --no-force                            Keeps the variable order of the model instead of
+                                        applying the static variable order computed by
+                                        the FORCE heuristic before the exploration. This
+                                        order is only applied when it does not increase
+                                        the number of nodes.
+  --reorder                             Optimises the variable order using sifting
+                                        before the exploration.
+  --verbose                             Prints the static variable order, the number of
+                                        nodes after every iteration and the performance
+                                        metrics.
+  --help                                Prints this help text.";
+
+/// The strategy that is used to explore the state space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy
+{
+    Bfs,
+    Chaining,
+    Saturation,
+}
+
 pub struct Config
 {
   pub filename: String,
+  pub strategy: Strategy,
+  pub reorder: bool,
+  pub force: bool,
+  pub verbose: bool,
 }
 
 impl Config
@@ -51,11 +218,51 @@ impl Config
     {
         args.next(); // The first argument is the executable's location.
 
-        let filename = match args.next() {
+        let mut filename = None;
+        let mut strategy = Strategy::Bfs;
+        let mut reorder = false;
+        let mut force = true;
+        let mut verbose = false;
+
+        while let Some(arg) = args.next()
+        {
+            if arg == "--strategy"
+            {
+                strategy = match args.next().as_deref() {
+                    Some("bfs") => Strategy::Bfs,
+                    Some("chaining") => Strategy::Chaining,
+                    Some("saturation") => Strategy::Saturation,
+                    Some(_) => return Err("Unknown strategy, expected bfs, chaining or saturation"),
+                    None => return Err("Requires a strategy after --strategy"),
+                };
+            }
+            else if arg == "--reorder"
+            {
+                reorder = true;
+            }
+            else if arg == "--no-force"
+            {
+                force = false;
+            }
+            else if arg == "--verbose"
+            {
+                verbose = true;
+            }
+            else if filename.is_none()
+            {
+                filename = Some(arg);
+            }
+            else
+            {
+                return Err("Unexpected argument");
+            }
+        }
+
+        let filename = match filename {
             Some(arg) => arg,
             None => return Err("Requires model filename")
         };
 
-        Ok(Config { filename })
+        Ok(Config { filename, strategy, reorder, force, verbose })
     }
-}
\ No newline at end of file
+}
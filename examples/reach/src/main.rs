@@ -1,10 +1,16 @@
 use std::env;
 use std::process;
 
-use reach::{run, Config};
+use reach::{run, Config, USAGE};
 
 fn main()
 {
+    if env::args().any(|arg| arg == "--help")
+    {
+        println!("{}", USAGE);
+        return;
+    }
+
     let config = Config::new(env::args()).unwrap_or_else(
         |err| 
         { 
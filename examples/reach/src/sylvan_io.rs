@@ -5,10 +5,13 @@ use std::error::Error;
 
 use ldd::Value;
 
+#[derive(Clone)]
 pub struct Transition
 {
     pub relation: ldd::Ldd,
     pub meta: ldd::Ldd,
+    pub read_proj: Vec<Value>, // The positions read by this transition group.
+    pub write_proj: Vec<Value>, // The positions written by this transition group.
 }
 
 /// Returns the (initial state, transitions) read from the file in Sylvan's format.
@@ -34,6 +37,8 @@ pub fn load_model(storage: &mut ldd::Storage, filename: &str) -> Result<(ldd::Ld
             Transition {
                 relation: storage.empty_set().clone(),
                 meta: ldd::compute_meta(storage, &read_proj, &write_proj),
+                read_proj,
+                write_proj,
             }
         );
     }
@@ -86,16 +91,21 @@ impl SylvanReader
           bytes[2..4].copy_from_slice(&b.to_le_bytes()[0..2]); 
           let value = u32::from_le_bytes(bytes);
 
-          let copy = right & 0x10000;
-          if copy != 0
-          {
-              panic!("We do not yet deal with copy nodes.");
-          }
+          let copy = b & 0x10000;
           
           let down = self.node_from_index(storage, down);
           let right = self.node_from_index(storage, right);
 
-          let ldd = storage.insert(value as Value, &down, &right);
+          let ldd = if copy != 0
+          {
+              // Sylvan stores the copy node first in the right chain, but our copy nodes are always last.
+              let copy = storage.insert_copy(&down);
+              ldd::union(storage, &right, &copy)
+          }
+          else
+          {
+              storage.insert(value as Value, &down, &right)
+          };
           self.indexed_set.insert(self.last_index, ldd);
           
           self.last_index += 1;
@@ -170,6 +180,129 @@ fn read_projection(file: &mut File) -> Result<(Vec<Value>,  Vec<Value>), Box<dyn
 mod test
 {
     use super::*;
+    use std::collections::HashSet;
+
+    /// Returns the successor of the state for the given vector of the relation, if it exists. Copy nodes are read as any value and write the value that was read.
+    fn explicit_successor(state: &[Value], vector: &[Value], read_proj: &[Value], write_proj: &[Value]) -> Option<Vec<Value>>
+    {
+        let mut result = state.to_vec();
+        let mut values = vector.iter();
+        for i in 0..state.len() as Value
+        {
+            if read_proj.contains(&i)
+            {
+                let value = *values.next()?;
+                if value != ldd::COPY && value != state[i as usize]
+                {
+                    return None;
+                }
+            }
+
+            if write_proj.contains(&i)
+            {
+                let value = *values.next()?;
+                if value != ldd::COPY
+                {
+                    result[i as usize] = value;
+                }
+            }
+        }
+
+        // Any remaining value of the relation is an action label, which is ignored.
+        Some(result)
+    }
+
+    /// A node as it is stored in the file, where a copy node is only marked by its flag.
+    struct RawNode
+    {
+        value: Value,
+        down: u64,
+        right: u64,
+        copy: bool,
+    }
+
+    /// Reads the nodes of a single LDD from the file into nodes, where the node with index i is stored at i - 2, and returns the index of its root.
+    fn read_raw_ldd(file: &mut File, nodes: &mut Vec<RawNode>) -> u64
+    {
+        let count = read_u64(file).unwrap();
+        for _ in 0..count
+        {
+            let a = read_u64(file).unwrap();
+            let b = read_u64(file).unwrap();
+            nodes.push(RawNode {
+                value: ((a >> 48) | ((b & 0xffff) << 16)) as Value,
+                down: b >> 17,
+                right: (a & 0x0000ffffffffffff) >> 1,
+                copy: b & 0x10000 != 0,
+            });
+        }
+
+        read_u64(file).unwrap()
+    }
+
+    /// Returns the vectors of the LDD with the given index, where every copy node is represented by [ldd::COPY].
+    fn raw_vectors(nodes: &[RawNode], index: u64) -> Vec<Vec<Value>>
+    {
+        match index
+        {
+            0 => Vec::new(),
+            1 => vec![Vec::new()],
+            _ => {
+                let node = &nodes[index as usize - 2];
+                let value = if node.copy { ldd::COPY } else { node.value };
+
+                let mut result: Vec<Vec<Value>> = raw_vectors(nodes, node.down).into_iter()
+                    .map(|vector| [vec![value], vector].concat())
+                    .collect();
+                result.extend(raw_vectors(nodes, node.right));
+                result
+            }
+        }
+    }
+
+    /// Returns the number of states of the model, computed by an explicit exploration. The LDDs are read from the
+    /// nodes as they are stored in the file, so this does not use [load_model] or any of the symbolic operations.
+    fn explicit_state_count(filename: &str) -> usize
+    {
+        let mut file = File::open(filename).unwrap();
+        let mut nodes = Vec::new();
+
+        let _vector_length = read_u32(&mut file).unwrap();
+        let _unused = read_u32(&mut file).unwrap();
+        let initial_state = read_raw_ldd(&mut file, &mut nodes);
+
+        let num_transitions = read_u32(&mut file).unwrap();
+        let projections: Vec<(Vec<Value>, Vec<Value>)> = (0..num_transitions)
+            .map(|_| read_projection(&mut file).unwrap())
+            .collect();
+        let relations: Vec<u64> = (0..num_transitions)
+            .map(|_| read_raw_ldd(&mut file, &mut nodes))
+            .collect();
+        let relations: Vec<Vec<Vec<Value>>> = relations.iter()
+            .map(|relation| raw_vectors(&nodes, *relation))
+            .collect();
+
+        let mut states: HashSet<Vec<Value>> = raw_vectors(&nodes, initial_state).into_iter().collect();
+        let mut todo: Vec<Vec<Value>> = states.iter().cloned().collect();
+        while let Some(state) = todo.pop()
+        {
+            for ((read_proj, write_proj), relation) in projections.iter().zip(&relations)
+            {
+                for vector in relation
+                {
+                    if let Some(successor) = explicit_successor(&state, vector, read_proj, write_proj)
+                    {
+                        if states.insert(successor.clone())
+                        {
+                            todo.push(successor);
+                        }
+                    }
+                }
+            }
+        }
+
+        states.len()
+    }
 
     #[test]
     fn test_load_anderson_4()
@@ -184,4 +317,26 @@ mod test
         let mut storage = ldd::Storage::new();
         let (_, _) = load_model(&mut storage, "models/collision.4.ldd").expect("Loading should work correctly");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_load_blocks_2()
+    {
+        let mut storage = ldd::Storage::new();
+        let (_, _) = load_model(&mut storage, "models/blocks.2.ldd").expect("Loading should work correctly");
+    }
+
+    // The blocks models use copy nodes, which are checked against an explicit exploration of the nodes in the file.
+    #[test]
+    fn test_blocks_2_explicit()
+    {
+        assert_eq!(explicit_state_count("models/blocks.2.ldd"), 7057, "Number of states does not match expected amount.");
+    }
+
+    // This test takes about ten seconds in release mode, run it using `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_blocks_3_explicit()
+    {
+        assert_eq!(explicit_state_count("models/blocks.3.ldd"), 695418, "Number of states does not match expected amount.");
+    }
+}
@@ -14,6 +14,122 @@ fn test_anderson()
 
     if let Ok(result) = run(&config)
     {
-        assert_eq!(result, 29641, "Number of states does not match expected amount.");
+        assert_eq!(result, Some(29641), "Number of states does not match expected amount.");
     }
-}
\ No newline at end of file
+}
+
+// A test for the chaining strategy on one of the given models.
+#[test]
+fn test_anderson_chaining()
+{
+    let args = [
+        String::from("path"),
+        String::from("models/anderson.4.ldd"),
+        String::from("--strategy"),
+        String::from("chaining"),
+    ];
+
+    let config = Config::new(args.iter().map(|s| s.to_string())).unwrap();
+
+    let result = run(&config).expect("Exploration should succeed");
+    assert_eq!(result, Some(29641), "Number of states does not match expected amount.");
+}
+
+// A test for the saturation strategy on one of the given models.
+#[test]
+fn test_anderson_saturation()
+{
+    let args = [
+        String::from("path"),
+        String::from("models/anderson.4.ldd"),
+        String::from("--strategy"),
+        String::from("saturation"),
+    ];
+
+    let config = Config::new(args.iter().map(|s| s.to_string())).unwrap();
+
+    let result = run(&config).expect("Exploration should succeed");
+    assert_eq!(result, Some(29641), "Number of states does not match expected amount.");
+}
+
+// A test for the variable reordering on one of the given models.
+#[test]
+fn test_anderson_reorder()
+{
+    let args = [
+        String::from("path"),
+        String::from("models/anderson.4.ldd"),
+        String::from("--reorder"),
+    ];
+
+    let config = Config::new(args.iter().map(|s| s.to_string())).unwrap();
+
+    let result = run(&config).expect("Exploration should succeed");
+    assert_eq!(result, Some(29641), "Number of states does not match expected amount.");
+}
+
+// A test for the variable order of the model itself on one of the given models.
+#[test]
+fn test_anderson_no_force()
+{
+    let args = [
+        String::from("path"),
+        String::from("models/anderson.4.ldd"),
+        String::from("--no-force"),
+    ];
+
+    let config = Config::new(args.iter().map(|s| s.to_string())).unwrap();
+
+    let result = run(&config).expect("Exploration should succeed");
+    assert_eq!(result, Some(29641), "Number of states does not match expected amount.");
+}
+
+// A test for a model with copy nodes in its relations.
+#[test]
+fn test_blocks_2()
+{
+    let args = [
+        String::from("path"),
+        String::from("models/blocks.2.ldd"),
+    ];
+
+    let config = Config::new(args.iter().map(|s| s.to_string())).unwrap();
+
+    let result = run(&config).expect("Exploration should succeed");
+    assert_eq!(result, Some(7057), "Number of states does not match expected amount.");
+}
+
+// This test takes 10 to 15 seconds in release mode, run it using `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn test_blocks_3()
+{
+    let args = [
+        String::from("path"),
+        String::from("models/blocks.3.ldd"),
+    ];
+
+    let config = Config::new(args.iter().map(|s| s.to_string())).unwrap();
+
+    let result = run(&config).expect("Exploration should succeed");
+    assert_eq!(result, Some(695418), "Number of states does not match expected amount.");
+}
+
+// This test takes about 14 minutes and 5GB of memory in release mode, run it using `cargo
+// test --release -- --ignored`. The expected number of states agrees with an explicit
+// exploration of the nodes in the model file, as in explicit_state_count in sylvan_io.rs,
+// but it has not been compared with the output of lddmc.
+#[test]
+#[ignore]
+fn test_blocks_4()
+{
+    let args = [
+        String::from("path"),
+        String::from("models/blocks.4.ldd"),
+    ];
+
+    let config = Config::new(args.iter().map(|s| s.to_string())).unwrap();
+
+    let result = run(&config).expect("Exploration should succeed");
+    assert_eq!(result, Some(104906622), "Number of states does not match expected amount.");
+}
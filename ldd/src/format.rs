@@ -15,6 +15,7 @@ pub fn fmt_node<'a>(storage: &'a Storage, ldd: &Ldd) -> Display<'a>
 }
 
 /// Prints the given LDD is the 'dot' format, which can be converted into an image using 'GraphViz'.
+#[allow(clippy::mutable_key_type)] // Ldd only hashes its index, the protection set is not part of the key.
 pub fn print_dot(storage: &Storage, f: &mut impl Write, ldd: &Ldd) -> io::Result<()>
 {
     write!(f, r#"
@@ -73,6 +74,7 @@ fn print(storage: &Storage, ldd: &Ldd, f: &mut fmt::Formatter<'_>) -> fmt::Resul
     Ok(())
 }
 
+#[allow(clippy::mutable_key_type)]
 fn print_node(storage: &Storage, f: &mut impl Write, marked: &mut HashSet<Ldd>, ldd: &Ldd) -> io::Result<()>
 {
     if marked.contains(ldd) || ldd == storage.empty_set() || ldd == storage.empty_vector()
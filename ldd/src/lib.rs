@@ -28,12 +28,26 @@
 //! \[Dijk18\] --- Tom van Dijk, Jaco van de Pol. Sylvan: multi-core framework
 //! for decision diagrams. International Journal on Software Tools for
 //! Technology Transfer. 19(6):675-696, 2017.
+//!
+//! \[Ciardo01\] --- Gianfranco Ciardo, Gerald Lüttgen, Radu Siminiceanu.
+//! Saturation: An efficient iteration strategy for symbolic state-space
+//! generation. TACAS 2001, LNCS 2031:328-342, 2001.
+//!
+//! \[Rudell93\] --- Richard Rudell. Dynamic variable ordering for ordered
+//! binary decision diagrams. ICCAD 1993, pages 42-47, 1993.
+//!
+//! \[Aloul03\] --- Fadi A. Aloul, Igor L. Markov, Karem A. Sakallah. FORCE: a
+//! fast and easy-to-implement variable-ordering heuristic. GLSVLSI 2003, pages
+//! 116-119, 2003.
 //! 
 extern crate static_assertions;
 
 mod storage;
 mod operations;
 mod format;
+mod saturation;
+mod statistics;
+mod reorder;
 pub mod iterators;
 
 #[cfg(test)]
@@ -41,4 +55,7 @@ mod test_utility;
 
 pub use storage::*;
 pub use operations::*;
-pub use format::*;
\ No newline at end of file
+pub use format::*;
+pub use saturation::*;
+pub use statistics::*;
+pub use reorder::*;
\ No newline at end of file
@@ -1,8 +1,12 @@
 use crate::{
-    cache_binary_op, cache_comm_binary_op, cache_terniary_op, cache_unary_function, iterators::*,
-    BinaryOperator, Data, DataRef, Ldd, LddRef, Storage, TernaryOperator, UnaryFunction, Value,
+    cache_binary_op, cache_binary_pair_op, cache_binary_predicate, cache_comm_binary_op,
+    cache_comm_binary_predicate, cache_indexed_op, cache_quaternary_op, cache_terniary_op, cache_unary_function,
+    cache_unary_function_f64, cache_unary_function_u128, iterators::*, BinaryOperator,
+    BinaryPairOperator, BinaryPredicate, Data, DataRef, IndexedOperator, Ldd, LddRef, QuaternaryOperator, Storage,
+    TernaryOperator, UnaryFunction, UnaryFunctionF64, UnaryFunctionU128, Value, COPY,
 };
 
+use rand::Rng;
 use std::cmp::{self, Ordering};
 
 /// Returns an LDD containing only the given vector, i.e., { vector }.
@@ -16,6 +20,68 @@ pub fn singleton(storage: &mut Storage, vector: &[Value]) -> Ldd {
     root
 }
 
+/// Returns the union of the set with the singleton set { vector }, i.e., set ∪
+/// { vector }. Only the nodes on the path to the vector are created, which
+/// avoids constructing the [singleton] first.
+pub fn union_vector(storage: &mut Storage, set: &LddRef, vector: &[Value]) -> Ldd {
+    if set == storage.empty_set() {
+        singleton(storage, vector)
+    } else if vector.is_empty() {
+        debug_assert_eq!(set, storage.empty_vector(), "vector must be as long as the vectors in set");
+        storage.protect(set)
+    } else {
+        let DataRef(value, down, right) = storage.get_ref(set);
+
+        match value.cmp(&vector[0]) {
+            Ordering::Less => {
+                let right_result = union_vector(storage, &right, vector);
+                storage.insert(value, &down, &right_result)
+            }
+            Ordering::Equal => {
+                let down_result = union_vector(storage, &down, &vector[1..]);
+                storage.insert(value, &down_result, &right)
+            }
+            Ordering::Greater => {
+                let down_result = singleton(storage, &vector[1..]);
+                storage.insert(vector[0], &down_result, set)
+            }
+        }
+    }
+}
+
+/// Returns the set without the given vector, i.e., set \ { vector }. Only the
+/// nodes on the path to the vector are created, which avoids constructing the
+/// [singleton] first.
+pub fn minus_vector(storage: &mut Storage, set: &LddRef, vector: &[Value]) -> Ldd {
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if vector.is_empty() {
+        debug_assert_eq!(set, storage.empty_vector(), "vector must be as long as the vectors in set");
+        storage.empty_set().clone()
+    } else {
+        let DataRef(value, down, right) = storage.get_ref(set);
+
+        match value.cmp(&vector[0]) {
+            Ordering::Less => {
+                let right_result = minus_vector(storage, &right, vector);
+                storage.insert(value, &down, &right_result)
+            }
+            Ordering::Equal => {
+                let down_result = minus_vector(storage, &down, &vector[1..]);
+                if down_result == *storage.empty_set() {
+                    storage.protect(&right)
+                } else {
+                    storage.insert(value, &down_result, &right)
+                }
+            }
+            Ordering::Greater => {
+                // The vector is not an element of the set.
+                storage.protect(set)
+            }
+        }
+    }
+}
+
 /// Computes a meta LDD that is suitable for the [project] function from the
 /// given projection indices.
 ///
@@ -68,28 +134,143 @@ pub fn project(storage: &mut Storage, set: &LddRef, proj: &LddRef) -> Ldd {
             "proj can be at most as high as set"
         );
 
-        let DataRef(proj_value, proj_down, _) = storage.get_ref(&proj);
-        let DataRef(value, down, right) = storage.get_ref(&set);
+        cache_binary_op(storage, BinaryOperator::Project, set, proj, |storage, set, proj| {
+            let DataRef(proj_value, proj_down, _) = storage.get_ref(proj);
+            let DataRef(value, down, right) = storage.get_ref(set);
 
-        match proj_value {
-            0 => {
-                let right_result = project(storage, &right, proj);
-                let down_result = project(storage, &down, &proj_down);
-                union(storage, &right_result, &down_result)
+            match proj_value {
+                0 => {
+                    let right_result = project(storage, &right, proj);
+                    let down_result = project(storage, &down, &proj_down);
+                    union(storage, &right_result, &down_result)
+                }
+                1 => {
+                    let right_result = project(storage, &right, proj);
+                    let down_result = project(storage, &down, &proj_down);
+                    if down_result == *storage.empty_set() {
+                        right_result
+                    } else {
+                        storage.insert(value, &down_result, &right_result)
+                    }
+                }
+                x => {
+                    panic!("proj has unexpected value {}", x);
+                }
             }
-            1 => {
-                let right_result = project(storage, &right, proj);
-                let down_result = project(storage, &down, &proj_down);
-                if down_result == *storage.empty_set() {
-                    right_result
-                } else {
-                    storage.insert(value, &down_result, &right_result)
+        })
+    }
+}
+
+/// Computes project(set, proj) \ avoid in a single traversal, where proj is
+/// equal to compute_proj([i_0, ..., i_k]). See [project] for more details.
+pub fn project_minus(storage: &mut Storage, set: &LddRef, proj: &LddRef, avoid: &LddRef) -> Ldd {
+    debug_assert_ne!(proj, storage.empty_set(), "proj must be a singleton");
+
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if avoid == storage.empty_set() {
+        project(storage, set, proj)
+    } else if proj == storage.empty_vector() {
+        // The projection is the empty vector, which is contained in avoid.
+        storage.empty_set().clone()
+    } else {
+        debug_assert_ne!(
+            set,
+            storage.empty_vector(),
+            "proj can be at most as high as set"
+        );
+
+        cache_terniary_op(storage, TernaryOperator::ProjectMinus, set, proj, avoid, |storage, set, proj, avoid| {
+            let DataRef(proj_value, proj_down, _) = storage.get_ref(proj);
+            let DataRef(value, down, right) = storage.get_ref(set);
+
+            match proj_value {
+                0 => {
+                    let right_result = project_minus(storage, &right, proj, avoid);
+                    let down_result = project_minus(storage, &down, &proj_down, avoid);
+                    union(storage, &right_result, &down_result)
+                }
+                1 => {
+                    let DataRef(avoid_value, avoid_down, avoid_right) = storage.get_ref(avoid);
+
+                    match value.cmp(&avoid_value) {
+                        Ordering::Less => {
+                            let right_result = project_minus(storage, &right, proj, avoid);
+                            let down_result = project(storage, &down, &proj_down);
+                            storage.insert(value, &down_result, &right_result)
+                        }
+                        Ordering::Equal => {
+                            let right_result = project_minus(storage, &right, proj, &avoid_right);
+                            let down_result = project_minus(storage, &down, &proj_down, &avoid_down);
+                            if down_result == *storage.empty_set() {
+                                right_result
+                            } else {
+                                storage.insert(value, &down_result, &right_result)
+                            }
+                        }
+                        Ordering::Greater => project_minus(storage, set, proj, &avoid_right),
+                    }
+                }
+                x => {
+                    panic!("proj has unexpected value {}", x);
                 }
             }
-            x => {
-                panic!("proj has unexpected value {}", x);
+        })
+    }
+}
+
+/// Returns the vectors of set that match the pattern on the levels given by
+/// proj, where proj is equal to compute_proj([i_0, ..., i_k]). All other
+/// levels are wildcards.
+///
+/// Formally, match_pattern(X, P, i_0 < ... < i_k) = { x in X | project(x, i_0 < ... < i_k) in P }.
+pub fn match_pattern(storage: &mut Storage, set: &LddRef, pattern: &LddRef, proj: &LddRef) -> Ldd {
+    debug_assert_ne!(proj, storage.empty_set(), "proj must be a singleton");
+
+    if set == storage.empty_set() || pattern == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if proj == storage.empty_vector() {
+        // If proj is not defined then the remaining levels are wildcards (proj is always zero)
+        storage.protect(set)
+    } else {
+        cache_terniary_op(storage, TernaryOperator::Match, set, pattern, proj, |storage, set, pattern, proj| {
+            let DataRef(proj_value, proj_down, _) = storage.get_ref(proj);
+            let DataRef(set_value, set_down, set_right) = storage.get_ref(set);
+
+            match proj_value {
+                0 => {
+                    // Every value matches at this level.
+                    let down_result = match_pattern(storage, &set_down, pattern, &proj_down);
+                    let right_result = match_pattern(storage, &set_right, pattern, proj);
+                    if down_result == *storage.empty_set() {
+                        right_result
+                    } else {
+                        storage.insert(set_value, &down_result, &right_result)
+                    }
+                }
+                1 => {
+                    // Only values in the pattern match at this level.
+                    let DataRef(pattern_value, pattern_down, pattern_right) = storage.get_ref(pattern);
+
+                    match set_value.cmp(&pattern_value) {
+                        Ordering::Less => match_pattern(storage, &set_right, pattern, proj),
+                        Ordering::Equal => {
+                            let down_result = match_pattern(storage, &set_down, &pattern_down, &proj_down);
+                            let right_result = match_pattern(storage, &set_right, &pattern_right, proj);
+                            if down_result == *storage.empty_set() {
+                                right_result
+                            } else {
+                                storage.insert(set_value, &down_result, &right_result)
+                            }
+                        }
+                        Ordering::Greater => match_pattern(storage, set, &pattern_right, proj),
+                    }
+                }
+                x => {
+                    panic!("proj has unexpected value {}", x);
+                }
             }
-        }
+        })
     }
 }
 
@@ -145,6 +326,11 @@ pub fn compute_meta(storage: &mut Storage, read_proj: &[Value], write_proj: &[Va
 ///   - 2 = only in write_proj.
 ///   - 3 = in both read_proj and write_proj (read phase).
 ///   - 4 = in both read_proj and write_proj (write phase).
+///
+/// The relation can contain copy nodes, see [COPY], at the levels where meta
+/// is 2 or 4. These write the value that was read, i.e., the value at that
+/// position is left unchanged. Similarly, copy nodes at the levels where meta
+/// is 1 or 3 read any value.
 pub fn relational_product(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta: &LddRef) -> Ldd {
     debug_assert_ne!(meta, storage.empty_set(), "proj must be a singleton");
 
@@ -161,51 +347,36 @@ pub fn relational_product(storage: &mut Storage, set: &LddRef, rel: &LddRef, met
             rel,
             meta,
             |storage, set, rel, meta| {
-                let DataRef(meta_value, meta_down, _) = storage.get_ref(&meta);
+                let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
 
                 match meta_value {
                     0 => {
                         // Consider all values on this level part of the output and continue with rest.
-                        let DataRef(value, down, right) = storage.get_ref(&set);
+                        let DataRef(value, down, right) = storage.get_ref(set);
 
                         let right_result =
-                            relational_product(storage, &right, &rel, &meta);
-                        let down_result = relational_product(storage, &down, &rel, &meta_down);
+                            relational_product(storage, &right, rel, meta);
+                        let down_result = relational_product(storage, &down, rel, &meta_down);
                         if down_result == *storage.empty_set() {
                             right_result
                         } else {
                             storage.insert(value, &down_result, &right_result)
                         }
                     }
-                    1 => {
+                    1 | 3 => {
                         // Read the values present in the relation and continue with these values in the set.
-                        let DataRef(set_value, set_down, set_right) = storage.get_ref(&set);
-                        let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(&rel);
-
-                        match set_value.cmp(&rel_value) {
-                            Ordering::Less => relational_product(storage, &set_right, &rel, &meta),
-                            Ordering::Equal => {
-                                let down_result =
-                                    relational_product(storage, &set_down, &rel_down, &meta_down);
-                                let right_result =
-                                    relational_product(storage, &set_right, &rel_right, &meta);
-                                if down_result == *storage.empty_set() {
-                                    right_result
-                                } else {
-                                    storage.insert(
-                                        set_value,
-                                        &down_result,
-                                        &right_result,
-                                    )
-                                }
-                            }
-                            Ordering::Greater => relational_product(storage, &set, &rel_right, &meta),
-                        }
+                        relational_product_read(storage, set, rel, meta).0
                     }
                     2 => {
+                        let DataRef(rel_value, rel_down, _) = storage.get_ref(rel);
+                        if rel_value == COPY {
+                            // Keep the values present in the set.
+                            return relational_product_copy(storage, set, &rel_down, &meta_down);
+                        }
+
                         // All values in set should be considered.
                         let mut combined = storage.empty_set().clone();
-                        let mut current = storage.protect(&set);
+                        let mut current = storage.protect(set);
                         loop {
                             let DataRef(_, set_down, set_right) = storage.get_ref(&current);
                             combined = union(storage, &combined, &set_down);
@@ -216,46 +387,10 @@ pub fn relational_product(storage: &mut Storage, set: &LddRef, rel: &LddRef, met
                             current = storage.protect(&set_right);
                         }
 
-                        // Write the values present in the relation.
-                        let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(&rel);
-
-                        let down_result =
-                            relational_product(storage, &combined, &rel_down, &meta_down);
-                        let right_result = relational_product(storage, &set, &rel_right, &meta);
-                        if down_result == *storage.empty_set() {
-                            right_result
-                        } else {
-                            storage.insert(rel_value, &down_result, &right_result)
-                        }
-                    }
-                    3 => {
-                        let DataRef(set_value, set_down, set_right) = storage.get_ref(&set);
-                        let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(&rel);
-
-                        match set_value.cmp(&rel_value) {
-                            Ordering::Less => relational_product(storage, &set_right, &rel, &meta),
-                            Ordering::Equal => {
-                                let down_result =
-                                    relational_product(storage, &set_down, &rel_down, &meta_down);
-                                let right_result =
-                                    relational_product(storage, &set_right, &rel_right, &meta);
-                                union(storage, &down_result, &right_result)
-                            }
-                            Ordering::Greater => relational_product(storage, &set, &rel_right, &meta),
-                        }
+                        relational_product_write(storage, set, &combined, rel, meta)
                     }
                     4 => {
-                        // Write the values present in the relation.
-                        let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(&rel);
-
-                        let down_result =
-                            relational_product(storage, &set, &rel_down, &meta_down);
-                        let right_result = relational_product(storage, &set, &rel_right, &meta);
-                        if down_result == *storage.empty_set() {
-                            right_result
-                        } else {
-                            storage.insert(rel_value, &down_result, &right_result)
-                        }
+                        relational_product_write(storage, set, set, rel, meta)
                     }
                     x => {
                         panic!("meta has unexpected value: {}", x);
@@ -266,177 +401,1483 @@ pub fn relational_product(storage: &mut Storage, set: &LddRef, rel: &LddRef, met
     }
 }
 
-/// Returns the largest subset of 'a' that does not contains elements of 'b', i.e., set difference.
-pub fn minus(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
-    if a == b || a == storage.empty_set() {
-        storage.empty_set().clone()
-    } else if b == storage.empty_set() {
-        storage.protect(a)
-    } else {
-        cache_binary_op(storage, BinaryOperator::Minus, a, b, |storage, a, b| {
-            let DataRef(a_value, a_down, a_right) = storage.get_ref(&a);
-            let DataRef(b_value, b_down, b_right) = storage.get_ref(&b);
+/// Reads the values of the set that are present in the relation by merging
+/// their right chains, where meta is 1 or 3.
+///
+/// A copy node in the relation matches every value of the set, but it is the
+/// last node of the right chain. Therefore, its down is returned such that it
+/// can be applied to the values of the set that have been read before.
+fn relational_product_read(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta: &LddRef) -> (Ldd, Option<Ldd>) {
+    if rel == storage.empty_set() {
+        return (storage.empty_set().clone(), None);
+    }
 
-            match a_value.cmp(&b_value) {
-                Ordering::Less => {
-                    let right_result = minus(storage, &a_right, &b);
-                    storage.insert(a_value, &a_down, &right_result)
-                }
-                Ordering::Equal => {
-                    let down_result = minus(storage, &a_down, &b_down);
-                    let right_result = minus(storage, &a_right, &b_right);
-                    if down_result == *storage.empty_set() {
-                        right_result
-                    } else {
-                        storage.insert(a_value, &down_result,& right_result)
-                    }
-                }
-                Ordering::Greater => minus(storage, &a, &b_right),
-            }
-        })
+    let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(rel);
+    if rel_value == COPY {
+        let copy_down = storage.protect(&rel_down);
+        let result = relational_product_read_copy(storage, set, &copy_down, meta);
+        return (result, Some(copy_down));
+    } else if set == storage.empty_set() {
+        return (storage.empty_set().clone(), copy_down(storage, &rel_right));
     }
-}
 
-/// Returns the union of the given LDDs, i.e., a ∪ b.
-pub fn union(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
-    if a == b {
-        storage.protect(a)
-    } else if a == storage.empty_set() {
-        storage.protect(b)
-    } else if b == storage.empty_set() {
-        storage.protect(a)
-    } else {
-        cache_comm_binary_op(storage, BinaryOperator::Union, a, b, |storage, a, b| {
-            let DataRef(a_value, a_down, a_right) = storage.get_ref(a);
-            let DataRef(b_value, b_down, b_right) = storage.get_ref(b);
+    let DataRef(set_value, set_down, set_right) = storage.get_ref(set);
+    let (mut result, copy_down) = match set_value.cmp(&rel_value) {
+        Ordering::Less => relational_product_read(storage, &set_right, rel, meta),
+        Ordering::Equal => {
+            let (right_result, copy_down) = relational_product_read(storage, &set_right, &rel_right, meta);
+            let result = relational_product_read_value(storage, set_value, &set_down, &rel_down, meta, &right_result);
+            (result, copy_down)
+        }
+        Ordering::Greater => return relational_product_read(storage, set, &rel_right, meta),
+    };
 
-            match a_value.cmp(&b_value) {
-                Ordering::Less => {
-                    let result = union(storage, &a_right, &b);
-                    storage.insert(a_value, &a_down, &result)
-                }
-                Ordering::Equal => {
-                    let down_result = union(storage, &a_down, &b_down);
-                    let right_result = union(storage, &a_right, &b_right);
-                    storage.insert(a_value, &down_result, &right_result)
-                }
-                Ordering::Greater => {
-                    let result = union(storage, &a, &b_right);
-                    storage.insert(b_value, &b_down, &result)
-                }
-            }
-        })
+    if let Some(copy_down) = &copy_down {
+        // The copy node also reads this value.
+        result = relational_product_read_value(storage, set_value, &set_down, copy_down, meta, &result);
     }
-}
 
-/// Interleave the vectors of two equal height ldds.
-pub fn merge(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
-    if a == storage.empty_vector() {
-        storage.protect(b)
-    } else if b == storage.empty_vector() {
-        storage.protect(a)
-    } else if a == storage.empty_set() || b == storage.empty_set() {
-        storage.empty_set().clone()
-    } else {
-        cache_binary_op(storage, BinaryOperator::Merge, a, b, |storage, a, b| {
-            let DataRef(value, down, right) = storage.get_ref(&a);
+    (result, copy_down)
+}
 
-            let down_result = merge(storage, &b, &down);
-            let right_result = merge(storage, &right, &b);
+/// Returns the result of reading the given value, where set and rel are below
+/// the value, together with the result for the values to the right of it.
+fn relational_product_read_value(storage: &mut Storage, value: Value, set: &LddRef, rel: &LddRef, meta: &LddRef, right: &LddRef) -> Ldd {
+    let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
 
-            storage.insert(value, &down_result, &right_result)
-        })
+    if meta_value == 1 {
+        let down_result = relational_product(storage, set, rel, &meta_down);
+        insert_union(storage, value, &down_result, right)
+    } else {
+        let write_result = relational_product_read_write(storage, value, set, rel, &meta_down);
+        union(storage, &write_result, right)
     }
 }
 
-/// Appends the given value to every vector in the set represented by the given ldd.
-pub fn append(storage: &mut Storage, ldd: &LddRef, value: Value) -> Ldd {
-    if ldd == storage.empty_set() {
-        storage.empty_set().clone()
-    } else if ldd == storage.empty_vector() {
-        singleton(storage, &[value])
-    } else {
-        // Traverse the ldd.
-        let DataRef(val, down, right) = storage.get_ref(&ldd);
-
-        let down_result = append(storage, &down, value);
-        let right_result = append(storage, &right, value);
+/// Computes the write phase for the given value that was read, where set is
+/// below the read value and meta is the write phase.
+fn relational_product_read_write(storage: &mut Storage, value: Value, set: &LddRef, rel: &LddRef, meta: &LddRef) -> Ldd {
+    let result = relational_product(storage, set, rel, meta);
 
-        storage.insert(val, &down_result, &right_result)
+    if let Some(copy_down) = copy_down(storage, rel) {
+        // Write the value that was read.
+        let DataRef(_, meta_down, _) = storage.get_ref(meta);
+        let copy_result = relational_product(storage, set, &copy_down, &meta_down);
+        insert_union(storage, value, &copy_result, &result)
+    } else {
+        result
     }
 }
 
-/// Returns true iff the set contains the vector.
-pub fn element_of(storage: &Storage, vector: &[Value], ldd: &Ldd) -> bool {
-    if vector.is_empty() {
-        ldd == storage.empty_vector()
-    } else if ldd == storage.empty_vector() {
-        false
+/// Applies a copy node at a read level, which matches every value of the set,
+/// where rel is the relation below the copy node.
+fn relational_product_read_copy(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta: &LddRef) -> Ldd {
+    let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
+
+    if meta_value == 1 {
+        relational_product_copy(storage, set, rel, &meta_down)
     } else {
-        for Data(value, down, _) in iter_right(storage, ldd) {
-            match value.cmp(&vector[0]) {
-                Ordering::Less => {
-                    continue;
-                }
-                Ordering::Equal => {
-                    return element_of(storage, &vector[1..], &down);
-                }
-                Ordering::Greater => {
-                    return false;
-                }
-            }
+        // Every value of the set is read and continues with the write phase.
+        let mut result = storage.empty_set().clone();
+        let mut current = storage.protect(set);
+        while current != *storage.empty_set() {
+            let DataRef(value, down, right) = storage.get_ref(&current);
+            let write_result = relational_product_read_write(storage, value, &down, rel, &meta_down);
+            result = union(storage, &result, &write_result);
+            current = storage.protect(&right);
         }
 
-        false
+        result
     }
 }
 
-/// Returns the number of elements in the set.
-pub fn len(storage: &mut Storage, set: &LddRef) -> usize {
-    if set == storage.empty_set() {
-        0
-    } else if set == storage.empty_vector() {
-        1
-    } else {
-        cache_unary_function(storage, UnaryFunction::Len, set, |storage, a| {
-            let mut result: usize = 0;
+/// Writes the values present in the relation and continues with down for
+/// every written value, where set is only used to keep its values for copy
+/// nodes.
+fn relational_product_write(storage: &mut Storage, set: &LddRef, down: &LddRef, rel: &LddRef, meta: &LddRef) -> Ldd {
+    if rel == storage.empty_set() {
+        return storage.empty_set().clone();
+    }
 
-            let mut current = storage.protect(&a);
-            while current != *storage.empty_set() {
-                // Progress to the right LDD.
-                let DataRef(_, down, right) = storage.get_ref(&current);
-                result += len(storage, &down);
-                current = storage.protect(&right);
-            }
+    let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
+    let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(rel);
 
-            result
-        })
+    if rel_value == COPY {
+        if meta_value == 2 {
+            // Keep the values present in the set.
+            relational_product_copy(storage, set, &rel_down, &meta_down)
+        } else {
+            // The value that was read is written in the read phase.
+            storage.empty_set().clone()
+        }
+    } else {
+        // The right result can contain values written by a copy node.
+        let down_result = relational_product(storage, down, &rel_down, &meta_down);
+        let right_result = relational_product_write(storage, set, down, &rel_right, meta);
+        insert_union(storage, rel_value, &down_result, &right_result)
     }
 }
 
-/// Returns the height of the LDD tree.
-pub fn height(storage: &Storage, ldd: &LddRef) -> u64 {
-    if ldd == storage.empty_set() || ldd == storage.empty_vector() {
-        0
+/// Keeps every value of the set and continues with the relation below a copy
+/// node, where meta is the meta below the copy node.
+fn relational_product_copy(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta: &LddRef) -> Ldd {
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
     } else {
-        // Since all children have the same height we only have to look at the down node.
-        let DataRef(_, down, _) = storage.get_ref(ldd);
+        let DataRef(value, down, right) = storage.get_ref(set);
 
-        height(storage, &down) + 1
+        let down_result = relational_product(storage, &down, rel, meta);
+        let right_result = relational_product_copy(storage, &right, rel, meta);
+        if down_result == *storage.empty_set() {
+            right_result
+        } else {
+            storage.insert(value, &down_result, &right_result)
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::fmt_node;
-    use crate::test_utility::*;
+/// Returns the down of the copy node in the right chain of rel, if it exists.
+fn copy_down(storage: &mut Storage, rel: &LddRef) -> Option<Ldd> {
+    let mut current = rel.index();
+    while current != storage.empty_set().index() {
+        let node = LddRef::new(current);
+        let DataRef(value, down, right) = storage.get_ref(&node);
+        if value == COPY {
+            return Some(storage.protect(&down));
+        }
+        current = right.index();
+    }
 
-    use rand::Rng;
-    use std::collections::HashSet;
-    use std::ops::Sub;
+    None
+}
 
-    // Compare the LDD element_of implementation for random inputs.
+/// Returns the down of the node with the given value in the right chain of set, if it exists.
+fn find_down(storage: &mut Storage, set: &LddRef, value: Value) -> Option<Ldd> {
+    let mut current = storage.protect(set);
+    while current != *storage.empty_set() {
+        let Data(current_value, down, right) = storage.get(&current);
+        match current_value.cmp(&value) {
+            Ordering::Less => current = right,
+            Ordering::Equal => return Some(down),
+            Ordering::Greater => break,
+        }
+    }
+
+    None
+}
+
+/// Returns { value x | x in down } ∪ right, which is node(value, down, right)
+/// whenever value is smaller than the values in right. Otherwise, right
+/// contains values that were written by a copy node.
+fn insert_union(storage: &mut Storage, value: Value, down: &LddRef, right: &LddRef) -> Ldd {
+    if down == storage.empty_set() {
+        storage.protect(right)
+    } else if right == storage.empty_set() || value < storage.value(right) {
+        storage.insert(value, down, right)
+    } else {
+        let empty_set = storage.empty_set().clone();
+        let single = storage.insert(value, down, &empty_set);
+        union(storage, &single, right)
+    }
+}
+
+/// Computes the set of vectors in universe from which the given set can be reached in one step as defined by the sparse relation rel, i.e., the pre-image of [relational_product]. Requires that meta = compute_meta(read_proj, write_proj).
+///
+/// # Details
+///
+/// Formal definition of the function. relational_prev(R, S, U, read_proj, write_proj) = { x in U | project(x, read_proj) = x' and (x', y') in R and x[write_proj := y'] in S }
+/// where R is the relation, S the set and U the universe.
+///
+/// The values of positions that are written, but not read, are unconstrained in
+/// the pre-image. Therefore, the universe is required to obtain a finite
+/// result, which is typically the set of reachable states. The meta encoding is
+/// the same as for [relational_product].
+pub fn relational_prev(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta: &LddRef, universe: &LddRef) -> Ldd {
+    debug_assert_ne!(meta, storage.empty_set(), "proj must be a singleton");
+
+    if meta == storage.empty_vector() {
+        // If meta is not defined then the rest is not in the relation (meta is always zero)
+        intersect(storage, set, universe)
+    } else if set == storage.empty_set() || rel == storage.empty_set() || universe == storage.empty_set() {
+        storage.empty_set().clone()
+    } else {
+        cache_quaternary_op(
+            storage,
+            QuaternaryOperator::RelationalPrev,
+            set,
+            rel,
+            meta,
+            universe,
+            |storage, set, rel, meta, universe| {
+                let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
+
+                match meta_value {
+                    0 => {
+                        // The value is left unchanged so it must be both in the set and universe.
+                        let DataRef(set_value, set_down, set_right) = storage.get_ref(set);
+                        let DataRef(uni_value, uni_down, uni_right) = storage.get_ref(universe);
+
+                        match set_value.cmp(&uni_value) {
+                            Ordering::Less => relational_prev(storage, &set_right, rel, meta, universe),
+                            Ordering::Equal => {
+                                let down_result = relational_prev(storage, &set_down, rel, &meta_down, &uni_down);
+                                let right_result = relational_prev(storage, &set_right, rel, meta, &uni_right);
+                                if down_result == *storage.empty_set() {
+                                    right_result
+                                } else {
+                                    storage.insert(set_value, &down_result, &right_result)
+                                }
+                            }
+                            Ordering::Greater => relational_prev(storage, set, rel, meta, &uni_right),
+                        }
+                    }
+                    1 | 3 => {
+                        // Read the values present in the relation that are also in the universe.
+                        relational_prev_read(storage, set, rel, meta, universe).0
+                    }
+                    2 => {
+                        // Any value in the universe can be overwritten by the values present in the relation.
+                        let DataRef(uni_value, uni_down, uni_right) = storage.get_ref(universe);
+
+                        let mut down_result = relational_prev_write(storage, set, rel, &meta_down, &uni_down);
+                        if let (Some(copy_down), Some(set_down)) = (copy_down(storage, rel), find_down(storage, set, uni_value)) {
+                            // The value is left unchanged so it must also be in the set.
+                            let copy_result = relational_prev(storage, &set_down, &copy_down, &meta_down, &uni_down);
+                            down_result = union(storage, &down_result, &copy_result);
+                        }
+
+                        let right_result = relational_prev(storage, set, rel, meta, &uni_right);
+                        if down_result == *storage.empty_set() {
+                            right_result
+                        } else {
+                            storage.insert(uni_value, &down_result, &right_result)
+                        }
+                    }
+                    4 => {
+                        // The universe has already been advanced in the read phase.
+                        relational_prev_write(storage, set, rel, &meta_down, universe)
+                    }
+                    x => {
+                        panic!("meta has unexpected value: {}", x);
+                    }
+                }
+            },
+        )
+    }
+}
+
+/// Reads the values of the universe that are present in the relation by
+/// merging their right chains, where meta is 1 or 3. For meta 1 the values are
+/// left unchanged, so they must also be present in the set.
+///
+/// As for [relational_product_read], the down of the copy node in the
+/// relation is returned such that it can be applied to the values of the
+/// universe that have been read before.
+fn relational_prev_read(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta: &LddRef, universe: &LddRef) -> (Ldd, Option<Ldd>) {
+    if rel == storage.empty_set() {
+        return (storage.empty_set().clone(), None);
+    }
+
+    let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(rel);
+    if rel_value == COPY {
+        let copy_down = storage.protect(&rel_down);
+        let result = relational_prev_read_copy(storage, set, &copy_down, meta, universe);
+        return (result, Some(copy_down));
+    } else if set == storage.empty_set() || universe == storage.empty_set() {
+        return (storage.empty_set().clone(), copy_down(storage, &rel_right));
+    }
+
+    let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
+    let DataRef(uni_value, uni_down, uni_right) = storage.get_ref(universe);
+
+    if meta_value == 1 {
+        // The value is read and left unchanged so it must be in the set, the relation and the universe.
+        let DataRef(set_value, set_down, set_right) = storage.get_ref(set);
+        match set_value.cmp(&uni_value) {
+            Ordering::Less => return relational_prev_read(storage, &set_right, rel, meta, universe),
+            Ordering::Equal => {}
+            Ordering::Greater => return relational_prev_read(storage, set, rel, meta, &uni_right),
+        }
+
+        let (mut result, copy_down) = match set_value.cmp(&rel_value) {
+            Ordering::Less => relational_prev_read(storage, &set_right, rel, meta, &uni_right),
+            Ordering::Equal => {
+                let down_result = relational_prev(storage, &set_down, &rel_down, &meta_down, &uni_down);
+                let (right_result, copy_down) = relational_prev_read(storage, &set_right, &rel_right, meta, &uni_right);
+                (insert_union(storage, set_value, &down_result, &right_result), copy_down)
+            }
+            Ordering::Greater => return relational_prev_read(storage, set, &rel_right, meta, universe),
+        };
+
+        if let Some(copy_down) = &copy_down {
+            // The copy node also reads this value.
+            let copy_result = relational_prev(storage, &set_down, copy_down, &meta_down, &uni_down);
+            result = insert_union(storage, set_value, &copy_result, &result);
+        }
+
+        (result, copy_down)
+    } else {
+        let (mut result, copy_down) = match uni_value.cmp(&rel_value) {
+            Ordering::Less => relational_prev_read(storage, set, rel, meta, &uni_right),
+            Ordering::Equal => {
+                let down_result = relational_prev_read_write(storage, set, uni_value, &rel_down, &meta_down, &uni_down);
+                let (right_result, copy_down) = relational_prev_read(storage, set, &rel_right, meta, &uni_right);
+                (insert_union(storage, uni_value, &down_result, &right_result), copy_down)
+            }
+            Ordering::Greater => return relational_prev_read(storage, set, &rel_right, meta, universe),
+        };
+
+        if let Some(copy_down) = &copy_down {
+            // The copy node also reads this value.
+            let copy_result = relational_prev_read_write(storage, set, uni_value, copy_down, &meta_down, &uni_down);
+            result = insert_union(storage, uni_value, &copy_result, &result);
+        }
+
+        (result, copy_down)
+    }
+}
+
+/// Computes the pre-image of the write phase for the given value that was
+/// read, where universe is below the read value and meta is the write phase.
+fn relational_prev_read_write(storage: &mut Storage, set: &LddRef, value: Value, rel: &LddRef, meta: &LddRef, universe: &LddRef) -> Ldd {
+    let result = relational_prev(storage, set, rel, meta, universe);
+
+    if let (Some(copy_down), Some(set_down)) = (copy_down(storage, rel), find_down(storage, set, value)) {
+        // The value that was read is written so it must also be in the set.
+        let DataRef(_, meta_down, _) = storage.get_ref(meta);
+        let copy_result = relational_prev(storage, &set_down, &copy_down, &meta_down, universe);
+        union(storage, &result, &copy_result)
+    } else {
+        result
+    }
+}
+
+/// Applies a copy node at a read level, which matches every value of the
+/// universe, where rel is the relation below the copy node.
+fn relational_prev_read_copy(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta: &LddRef, universe: &LddRef) -> Ldd {
+    if universe == storage.empty_set() {
+        return storage.empty_set().clone();
+    }
+
+    let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
+    let DataRef(uni_value, uni_down, uni_right) = storage.get_ref(universe);
+
+    let down_result = if meta_value == 1 {
+        // The value is left unchanged so it must also be in the set.
+        match find_down(storage, set, uni_value) {
+            Some(set_down) => relational_prev(storage, &set_down, rel, &meta_down, &uni_down),
+            None => storage.empty_set().clone(),
+        }
+    } else {
+        relational_prev_read_write(storage, set, uni_value, rel, &meta_down, &uni_down)
+    };
+
+    let right_result = relational_prev_read_copy(storage, set, rel, meta, &uni_right);
+    if down_result == *storage.empty_set() {
+        right_result
+    } else {
+        storage.insert(uni_value, &down_result, &right_result)
+    }
+}
+
+/// Returns the union of relational_prev(set_down, rel_down, meta_down, universe)
+/// for every value written by rel that is also present in set.
+fn relational_prev_write(storage: &mut Storage, set: &LddRef, rel: &LddRef, meta_down: &LddRef, universe: &LddRef) -> Ldd {
+    let mut result = storage.empty_set().clone();
+    let mut set_current = storage.protect(set);
+    let mut rel_current = storage.protect(rel);
+
+    while set_current != *storage.empty_set() && rel_current != *storage.empty_set() {
+        let DataRef(set_value, set_down, set_right) = storage.get_ref(&set_current);
+        let DataRef(rel_value, rel_down, rel_right) = storage.get_ref(&rel_current);
+
+        match set_value.cmp(&rel_value) {
+            Ordering::Less => {
+                set_current = storage.protect(&set_right);
+            }
+            Ordering::Equal => {
+                let down_result = relational_prev(storage, &set_down, &rel_down, meta_down, universe);
+                result = union(storage, &result, &down_result);
+
+                set_current = storage.protect(&set_right);
+                rel_current = storage.protect(&rel_right);
+            }
+            Ordering::Greater => {
+                rel_current = storage.protect(&rel_right);
+            }
+        }
+    }
+
+    result
+}
+
+/// A step of [extend_relation] for a single level of the extended relation.
+#[derive(Clone, Copy)]
+enum ExtendStep {
+    /// Keep the level of the original relation.
+    Keep,
+    /// Insert a copy node, which does not consume a level of the original relation.
+    Copy,
+}
+
+/// Returns the meta of every position, where the read and write phases (3 and 4) are combined into 3.
+fn meta_positions(storage: &Storage, meta: &LddRef) -> Vec<Value> {
+    let meta = pick_one(storage, meta).expect("meta should be a singleton");
+    meta.into_iter().filter(|value| *value != 4).collect()
+}
+
+/// Returns the relation rel for old_meta rewritten to the equivalent relation
+/// for new_meta, which is useful to combine groups with different read and
+/// write projections by taking the [union] of their extended relations.
+///
+/// Every position must be read (written) in new_meta when it is read (written)
+/// in old_meta, and the additional reads and writes are copy nodes, see
+/// [COPY]. This is similar to lddmc_extend_rel in Sylvan.
+pub fn extend_relation(storage: &mut Storage, rel: &LddRef, old_meta: &LddRef, new_meta: &LddRef) -> Ldd {
+    let old_positions = meta_positions(storage, old_meta);
+    let new_positions = meta_positions(storage, new_meta);
+    assert!(old_positions.len() <= new_positions.len(), "new_meta should have at least as many positions as old_meta");
+
+    let mut steps = Vec::new();
+    for (i, new_value) in new_positions.iter().enumerate() {
+        let old_value = old_positions.get(i).copied().unwrap_or(0);
+        match (old_value, *new_value) {
+            (0, 0) => {}
+            (1, 1) | (2, 2) => steps.push(ExtendStep::Keep),
+            (3, 3) => steps.extend([ExtendStep::Keep, ExtendStep::Keep]),
+            (0, 1) | (0, 2) => steps.push(ExtendStep::Copy),
+            (0, 3) => steps.extend([ExtendStep::Copy, ExtendStep::Copy]),
+            (1, 3) => steps.extend([ExtendStep::Keep, ExtendStep::Copy]),
+            (2, 3) => steps.extend([ExtendStep::Copy, ExtendStep::Keep]),
+            (old_value, new_value) => panic!("cannot extend meta {} to meta {} at position {}", old_value, new_value, i),
+        }
+    }
+
+    storage
+        .operation_cache()
+        .clear_indexed(&IndexedOperator::ExtendRelation);
+    extend_relation_rec(storage, rel, &steps, 0)
+}
+
+/// Applies the steps starting at the given index to rel, see [extend_relation].
+fn extend_relation_rec(storage: &mut Storage, rel: &LddRef, steps: &[ExtendStep], index: usize) -> Ldd {
+    if rel == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if index == steps.len() {
+        storage.protect(rel)
+    } else {
+        cache_indexed_op(storage, IndexedOperator::ExtendRelation, rel, index, |storage, rel, index| {
+            match steps[index] {
+                ExtendStep::Keep => {
+                    let DataRef(value, down, right) = storage.get_ref(rel);
+                    let down_result = extend_relation_rec(storage, &down, steps, index + 1);
+                    let right_result = extend_relation_rec(storage, &right, steps, index);
+
+                    if down_result == *storage.empty_set() {
+                        right_result
+                    } else {
+                        storage.insert(value, &down_result, &right_result)
+                    }
+                }
+                ExtendStep::Copy => {
+                    let down_result = extend_relation_rec(storage, rel, steps, index + 1);
+
+                    if down_result == *storage.empty_set() {
+                        down_result
+                    } else {
+                        storage.insert_copy(&down_result)
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Returns the relation for applying r1 (for meta1) followed by r2 (for
+/// meta2) together with its meta, which reads and writes every position that
+/// is read or written by either relation.
+pub fn compose(storage: &mut Storage, r1: &LddRef, meta1: &LddRef, r2: &LddRef, meta2: &LddRef) -> (Ldd, Ldd) {
+    let meta = read_write_meta(storage, meta1, meta2);
+    let r1 = extend_relation(storage, r1, meta1, &meta);
+    let r2 = extend_relation(storage, r2, meta2, &meta);
+
+    (compose_rec(storage, &r1, &r2), meta)
+}
+
+/// Returns the transitive closure of the relation rel for meta, i.e., the
+/// relation that applies rel one or more times, together with its meta as
+/// defined by [compose]. It is computed by iterative squaring, which requires
+/// a number of compositions that is logarithmic in the longest path.
+pub fn transitive_closure(storage: &mut Storage, rel: &LddRef, meta: &LddRef) -> (Ldd, Ldd) {
+    cache_binary_pair_op(storage, BinaryPairOperator::TransitiveClosure, rel, meta, |storage, rel, meta| {
+        let closure_meta = read_write_meta(storage, meta, meta);
+        let mut closure = extend_relation(storage, rel, meta, &closure_meta);
+
+        loop {
+            // After i iterations the closure contains all paths up to length 2^i.
+            let squared = compose_rec(storage, &closure, &closure);
+            let next = union(storage, &closure, &squared);
+            if next == closure {
+                break;
+            }
+
+            closure = next;
+        }
+
+        (closure, closure_meta)
+    })
+}
+
+/// Returns the meta that reads and writes every position that is read or written in meta1 or meta2.
+fn read_write_meta(storage: &mut Storage, meta1: &LddRef, meta2: &LddRef) -> Ldd {
+    let positions1 = meta_positions(storage, meta1);
+    let positions2 = meta_positions(storage, meta2);
+
+    let length = cmp::max(positions1.len(), positions2.len());
+    let proj: Vec<Value> = (0..length)
+        .filter(|i| positions1.get(*i).copied().unwrap_or(0) != 0 || positions2.get(*i).copied().unwrap_or(0) != 0)
+        .map(|i| i as Value)
+        .collect();
+
+    compute_meta(storage, &proj, &proj)
+}
+
+/// Returns the (read, write, down) triples of a relation at a read phase, where the value of COPY is kept.
+fn read_write_pairs(storage: &mut Storage, rel: &LddRef) -> Vec<(Value, Value, Ldd)> {
+    let rel = storage.protect(rel);
+
+    let mut result = Vec::new();
+    for Data(read, write_chain, _) in iter_right(storage, &rel) {
+        for Data(write, down, _) in iter_right(storage, &write_chain) {
+            result.push((read, write, down));
+        }
+    }
+
+    result
+}
+
+/// Computes the composition of r1 and r2 for a meta that only contains read
+/// and write phases, see [compose].
+fn compose_rec(storage: &mut Storage, r1: &LddRef, r2: &LddRef) -> Ldd {
+    if r1 == storage.empty_set() || r2 == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if r1 == storage.empty_vector() {
+        debug_assert_eq!(r2, storage.empty_vector(), "relations should have the same height");
+        storage.empty_vector().clone()
+    } else {
+        cache_binary_op(storage, BinaryOperator::Compose, r1, r2, |storage, r1, r2| {
+            let pairs1 = read_write_pairs(storage, r1);
+            let pairs2 = read_write_pairs(storage, r2);
+
+            let empty_set = storage.empty_set().clone();
+            let mut result = storage.empty_set().clone();
+            for (read1, write1, down1) in &pairs1 {
+                // The value written by r1, which is COPY when both read and write are copies.
+                let written = if *write1 == COPY { *read1 } else { *write1 };
+
+                for (read2, write2, down2) in &pairs2 {
+                    let read = if *read2 == COPY {
+                        *read1
+                    } else if written == COPY {
+                        *read2
+                    } else if written == *read2 {
+                        *read1
+                    } else {
+                        continue;
+                    };
+
+                    let write = if *write2 != COPY {
+                        *write2
+                    } else if *read2 != COPY {
+                        *read2
+                    } else {
+                        written
+                    };
+
+                    let down = compose_rec(storage, down1, down2);
+                    if down != *storage.empty_set() {
+                        let write_node = storage.insert(write, &down, &empty_set);
+                        let read_node = storage.insert(read, &write_node, &empty_set);
+                        result = union(storage, &result, &read_node);
+                    }
+                }
+            }
+
+            result
+        })
+    }
+}
+
+/// Returns the inverse of the relation rel for meta, which relates y to x
+/// whenever rel relates x to y. The read and write phases (meta 3 and 4) are
+/// swapped, and the levels that are only read (meta 1) are their own inverse.
+/// As such, the [relational_product] of the inverse is a pre-image, which
+/// requires no universe unlike [relational_prev].
+///
+/// Returns None if a value that is written cannot be inverted, i.e., a level
+/// where meta is 2 contains a value that is not a copy node, or the read phase
+/// of a copy node is followed by a written value. The inverse of these writes
+/// would write any value, which cannot be represented by a relation. In that
+/// case [relational_prev] should be used instead.
+pub fn inverse(storage: &mut Storage, rel: &LddRef, meta: &LddRef) -> Option<Ldd> {
+    if is_invertible(storage, rel, meta) {
+        Some(inverse_rec(storage, rel, meta))
+    } else {
+        None
+    }
+}
+
+/// Returns true iff every value that is written by rel for meta can be inverted, see [inverse].
+fn is_invertible(storage: &mut Storage, rel: &LddRef, meta: &LddRef) -> bool {
+    if rel == storage.empty_set() || rel == storage.empty_vector() {
+        true
+    } else {
+        cache_binary_predicate(storage, BinaryPredicate::Invertible, rel, meta, |storage, rel, meta| {
+            let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
+
+            match meta_value {
+                0 => is_invertible(storage, rel, &meta_down),
+                1 | 2 => {
+                    let DataRef(value, down, right) = storage.get_ref(rel);
+
+                    (meta_value == 1 || value == COPY)
+                        && is_invertible(storage, &down, &meta_down)
+                        && is_invertible(storage, &right, meta)
+                }
+                3 => {
+                    let DataRef(_, meta_down, _) = storage.get_ref(&meta_down);
+
+                    read_write_pairs(storage, rel)
+                        .iter()
+                        .all(|(read, write, down)| (*read != COPY || *write == COPY) && is_invertible(storage, down, &meta_down))
+                }
+                x => {
+                    panic!("meta has unexpected value: {}", x);
+                }
+            }
+        })
+    }
+}
+
+/// Computes the inverse of rel for meta, see [inverse]. Requires that rel is invertible.
+fn inverse_rec(storage: &mut Storage, rel: &LddRef, meta: &LddRef) -> Ldd {
+    if rel == storage.empty_set() || rel == storage.empty_vector() {
+        storage.protect(rel)
+    } else {
+        cache_binary_op(storage, BinaryOperator::Inverse, rel, meta, |storage, rel, meta| {
+            let DataRef(meta_value, meta_down, _) = storage.get_ref(meta);
+
+            match meta_value {
+                0 => inverse_rec(storage, rel, &meta_down),
+                1 | 2 => {
+                    let DataRef(value, down, right) = storage.get_ref(rel);
+                    debug_assert!(meta_value == 1 || value == COPY, "the written value {} cannot be inverted", value);
+
+                    let down_result = inverse_rec(storage, &down, &meta_down);
+                    let right_result = inverse_rec(storage, &right, meta);
+                    if down_result == *storage.empty_set() {
+                        right_result
+                    } else {
+                        storage.insert(value, &down_result, &right_result)
+                    }
+                }
+                3 => {
+                    let DataRef(_, meta_down, _) = storage.get_ref(&meta_down);
+                    let empty_set = storage.empty_set().clone();
+
+                    let mut result = storage.empty_set().clone();
+                    for (read, write, down) in read_write_pairs(storage, rel) {
+                        debug_assert!(read != COPY || write == COPY, "the written value {} cannot be inverted", write);
+
+                        let down_result = inverse_rec(storage, &down, &meta_down);
+                        if down_result != *storage.empty_set() {
+                            // Copy nodes write the value that was read so these are their own inverse.
+                            let (read, write) = if write == COPY { (read, write) } else { (write, read) };
+                            let write_node = storage.insert(write, &down_result, &empty_set);
+                            let read_node = storage.insert(read, &write_node, &empty_set);
+                            result = union(storage, &result, &read_node);
+                        }
+                    }
+
+                    result
+                }
+                x => {
+                    panic!("meta has unexpected value: {}", x);
+                }
+            }
+        })
+    }
+}
+
+/// Returns the largest subset of 'a' that does not contains elements of 'b', i.e., set difference.
+pub fn minus(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
+    if a == b || a == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if b == storage.empty_set() {
+        storage.protect(a)
+    } else {
+        cache_binary_op(storage, BinaryOperator::Minus, a, b, |storage, a, b| {
+            let DataRef(a_value, a_down, a_right) = storage.get_ref(a);
+            let DataRef(b_value, b_down, b_right) = storage.get_ref(b);
+
+            match a_value.cmp(&b_value) {
+                Ordering::Less => {
+                    let right_result = minus(storage, &a_right, b);
+                    storage.insert(a_value, &a_down, &right_result)
+                }
+                Ordering::Equal => {
+                    let down_result = minus(storage, &a_down, &b_down);
+                    let right_result = minus(storage, &a_right, &b_right);
+                    if down_result == *storage.empty_set() {
+                        right_result
+                    } else {
+                        storage.insert(a_value, &down_result,& right_result)
+                    }
+                }
+                Ordering::Greater => minus(storage, a, &b_right),
+            }
+        })
+    }
+}
+
+/// Returns the union of the given LDDs, i.e., a ∪ b.
+pub fn union(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
+    if a == b {
+        storage.protect(a)
+    } else if a == storage.empty_set() {
+        storage.protect(b)
+    } else if b == storage.empty_set() {
+        storage.protect(a)
+    } else {
+        cache_comm_binary_op(storage, BinaryOperator::Union, a, b, |storage, a, b| {
+            let DataRef(a_value, a_down, a_right) = storage.get_ref(a);
+            let DataRef(b_value, b_down, b_right) = storage.get_ref(b);
+
+            match a_value.cmp(&b_value) {
+                Ordering::Less => {
+                    let result = union(storage, &a_right, b);
+                    storage.insert(a_value, &a_down, &result)
+                }
+                Ordering::Equal => {
+                    let down_result = union(storage, &a_down, &b_down);
+                    let right_result = union(storage, &a_right, &b_right);
+                    storage.insert(a_value, &down_result, &right_result)
+                }
+                Ordering::Greater => {
+                    let result = union(storage, a, &b_right);
+                    storage.insert(b_value, &b_down, &result)
+                }
+            }
+        })
+    }
+}
+
+/// Returns the pair (a ∪ b, b \ a) computed in a single traversal.
+///
+/// This is useful for the frontier computation of a breadth-first search,
+/// where a is the set of visited states and b the set of successors.
+pub fn zip(storage: &mut Storage, a: &LddRef, b: &LddRef) -> (Ldd, Ldd) {
+    if a == b {
+        (storage.protect(a), storage.empty_set().clone())
+    } else if a == storage.empty_set() {
+        (storage.protect(b), storage.protect(b))
+    } else if b == storage.empty_set() {
+        (storage.protect(a), storage.empty_set().clone())
+    } else {
+        cache_binary_pair_op(storage, BinaryPairOperator::Zip, a, b, |storage, a, b| {
+            let DataRef(a_value, a_down, a_right) = storage.get_ref(a);
+            let DataRef(b_value, b_down, b_right) = storage.get_ref(b);
+
+            match a_value.cmp(&b_value) {
+                Ordering::Less => {
+                    let (union_right, minus_right) = zip(storage, &a_right, b);
+                    (storage.insert(a_value, &a_down, &union_right), minus_right)
+                }
+                Ordering::Equal => {
+                    let (union_down, minus_down) = zip(storage, &a_down, &b_down);
+                    let (union_right, minus_right) = zip(storage, &a_right, &b_right);
+
+                    let union_result = storage.insert(a_value, &union_down, &union_right);
+                    if minus_down == *storage.empty_set() {
+                        (union_result, minus_right)
+                    } else {
+                        (union_result, storage.insert(a_value, &minus_down, &minus_right))
+                    }
+                }
+                Ordering::Greater => {
+                    let (union_right, minus_right) = zip(storage, a, &b_right);
+                    (storage.insert(b_value, &b_down, &union_right), storage.insert(b_value, &b_down, &minus_right))
+                }
+            }
+        })
+    }
+}
+
+/// Returns the intersection of the given LDDs, i.e., a ∩ b.
+pub fn intersect(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
+    if a == b {
+        storage.protect(a)
+    } else if a == storage.empty_set() || b == storage.empty_set() {
+        storage.empty_set().clone()
+    } else {
+        cache_comm_binary_op(storage, BinaryOperator::Intersect, a, b, |storage, a, b| {
+            let DataRef(a_value, a_down, a_right) = storage.get_ref(a);
+            let DataRef(b_value, b_down, b_right) = storage.get_ref(b);
+
+            match a_value.cmp(&b_value) {
+                Ordering::Less => intersect(storage, &a_right, b),
+                Ordering::Equal => {
+                    let down_result = intersect(storage, &a_down, &b_down);
+                    let right_result = intersect(storage, &a_right, &b_right);
+                    if down_result == *storage.empty_set() {
+                        right_result
+                    } else {
+                        storage.insert(a_value, &down_result, &right_result)
+                    }
+                }
+                Ordering::Greater => intersect(storage, a, &b_right),
+            }
+        })
+    }
+}
+
+/// Interleave the vectors of two equal height ldds.
+pub fn merge(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
+    if a == storage.empty_vector() {
+        storage.protect(b)
+    } else if b == storage.empty_vector() {
+        storage.protect(a)
+    } else if a == storage.empty_set() || b == storage.empty_set() {
+        storage.empty_set().clone()
+    } else {
+        cache_binary_op(storage, BinaryOperator::Merge, a, b, |storage, a, b| {
+            let DataRef(value, down, right) = storage.get_ref(a);
+
+            let down_result = merge(storage, b, &down);
+            let right_result = merge(storage, &right, b);
+
+            storage.insert(value, &down_result, &right_result)
+        })
+    }
+}
+
+/// Appends the given value to every vector in the set represented by the given ldd.
+pub fn append(storage: &mut Storage, ldd: &LddRef, value: Value) -> Ldd {
+    if ldd == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if ldd == storage.empty_vector() {
+        singleton(storage, &[value])
+    } else {
+        // Traverse the ldd.
+        let DataRef(val, down, right) = storage.get_ref(ldd);
+
+        let down_result = append(storage, &down, value);
+        let right_result = append(storage, &right, value);
+
+        storage.insert(val, &down_result, &right_result)
+    }
+}
+
+/// Returns the set { x ++ y | x in a and y in b } of concatenated vectors,
+/// i.e., every 'true' node of a is replaced by b.
+pub fn concat(storage: &mut Storage, a: &LddRef, b: &LddRef) -> Ldd {
+    if a == storage.empty_set() || b == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if a == storage.empty_vector() {
+        storage.protect(b)
+    } else if b == storage.empty_vector() {
+        storage.protect(a)
+    } else {
+        cache_binary_op(storage, BinaryOperator::Concat, a, b, |storage, a, b| {
+            let DataRef(value, down, right) = storage.get_ref(a);
+
+            let down_result = concat(storage, &down, b);
+            let right_result = concat(storage, &right, b);
+            storage.insert(value, &down_result, &right_result)
+        })
+    }
+}
+
+/// Returns the cartesian product of the given values per level, i.e., the
+/// set of vectors x such that x[i] is in values[i] for every level i.
+pub fn cube(storage: &mut Storage, values: &[Vec<Value>]) -> Ldd {
+    let empty_set = storage.empty_set().clone();
+    let mut result = storage.empty_vector().clone();
+
+    for level in values.iter().rev() {
+        let mut level = level.clone();
+        level.sort_unstable();
+        level.dedup();
+        if level.is_empty() {
+            return empty_set;
+        }
+
+        // Every value at this level shares the same down node.
+        let mut chain = empty_set.clone();
+        for value in level.iter().rev() {
+            chain = storage.insert(*value, &result, &chain);
+        }
+
+        result = chain;
+    }
+
+    result
+}
+
+/// Returns the set of vectors x where a value from values is inserted at the
+/// given position, i.e., the vectors x[0..position] ++ [v] ++ x[position..]
+/// for every v in values.
+pub fn insert_level(storage: &mut Storage, set: &LddRef, position: usize, values: &[Value]) -> Ldd {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    values.dedup();
+
+    storage
+        .operation_cache()
+        .clear_indexed(&IndexedOperator::InsertLevel);
+    insert_level_rec(storage, set, position, &values)
+}
+
+/// Inserts the level at the given position relative to the level of set, see [insert_level].
+fn insert_level_rec(storage: &mut Storage, set: &LddRef, position: usize, values: &[Value]) -> Ldd {
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if position == 0 {
+        let mut result = storage.empty_set().clone();
+        for value in values.iter().rev() {
+            result = storage.insert(*value, set, &result);
+        }
+
+        result
+    } else {
+        assert_ne!(set, storage.empty_vector(), "position should be at most the length of the vectors");
+
+        cache_indexed_op(storage, IndexedOperator::InsertLevel, set, position, |storage, set, position| {
+            let DataRef(value, down, right) = storage.get_ref(set);
+
+            let down_result = insert_level_rec(storage, &down, position - 1, values);
+            let right_result = insert_level_rec(storage, &right, position, values);
+            if down_result == *storage.empty_set() {
+                right_result
+            } else {
+                storage.insert(value, &down_result, &right_result)
+            }
+        })
+    }
+}
+
+/// Returns the set of vectors x[0..position] ++ x[position + 1..] for every
+/// x in set, i.e., the given level is existentially quantified.
+pub fn remove_level(storage: &mut Storage, set: &LddRef, position: usize) -> Ldd {
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
+    } else {
+        assert_ne!(set, storage.empty_vector(), "position should be less than the length of the vectors");
+
+        cache_indexed_op(storage, IndexedOperator::RemoveLevel, set, position, |storage, set, position| {
+            let DataRef(value, down, right) = storage.get_ref(set);
+
+            if position == 0 {
+                let right_result = remove_level(storage, &right, 0);
+                union(storage, &down, &right_result)
+            } else {
+                let down_result = remove_level(storage, &down, position - 1);
+                let right_result = remove_level(storage, &right, position);
+                storage.insert(value, &down_result, &right_result)
+            }
+        })
+    }
+}
+
+/// Returns the set of vectors x[0..position + 1] ++ x[position..] for every x
+/// in set, i.e., the value at the given level is repeated on the next level.
+pub fn duplicate_level(storage: &mut Storage, set: &LddRef, position: usize) -> Ldd {
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
+    } else {
+        assert_ne!(set, storage.empty_vector(), "position should be less than the length of the vectors");
+
+        cache_indexed_op(storage, IndexedOperator::DuplicateLevel, set, position, |storage, set, position| {
+            let DataRef(value, down, right) = storage.get_ref(set);
+
+            let down_result = if position == 0 {
+                let empty_set = storage.empty_set().clone();
+                storage.insert(value, &down, &empty_set)
+            } else {
+                duplicate_level(storage, &down, position - 1)
+            };
+
+            let right_result = duplicate_level(storage, &right, position);
+            storage.insert(value, &down_result, &right_result)
+        })
+    }
+}
+
+/// Returns the set of vectors where the values at the given position and the
+/// next position are swapped for every vector in set.
+pub fn swap_levels(storage: &mut Storage, set: &LddRef, position: usize) -> Ldd {
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
+    } else {
+        assert_ne!(set, storage.empty_vector(), "position + 1 should be less than the length of the vectors");
+
+        cache_indexed_op(storage, IndexedOperator::SwapLevels, set, position, |storage, set, position| {
+            let DataRef(value, down, right) = storage.get_ref(set);
+            let right_result = swap_levels(storage, &right, position);
+
+            if position == 0 {
+                // Exchange this node with every node of the chain below it.
+                assert_ne!(down, *storage.empty_vector(), "position + 1 should be less than the length of the vectors");
+
+                let empty_set = storage.empty_set().clone();
+                let mut result = right_result;
+                let down = storage.protect(&down);
+                for Data(down_value, down_down, _) in iter_right(storage, &down).collect::<Vec<_>>() {
+                    let node = storage.insert(value, &down_down, &empty_set);
+                    let node = storage.insert(down_value, &node, &empty_set);
+                    result = union(storage, &result, &node);
+                }
+
+                result
+            } else {
+                let down_result = swap_levels(storage, &down, position - 1);
+                storage.insert(value, &down_result, &right_result)
+            }
+        })
+    }
+}
+
+/// Returns the set of vectors y such that y[i] = x[permutation[i]] for every
+/// x in set, i.e., permutation[i] is the level that is moved to position i.
+/// Requires that permutation is a permutation of 0..n, where n is the length
+/// of the vectors.
+///
+/// The permutation is applied by swapping adjacent levels, see [swap_levels].
+pub fn permute(storage: &mut Storage, set: &LddRef, permutation: &[usize]) -> Ldd {
+    debug_assert!(is_permutation(permutation), "{:?} is not a permutation", permutation);
+
+    // The level of set that is currently at every position.
+    let mut order: Vec<usize> = (0..permutation.len()).collect();
+    let mut result = storage.protect(set);
+
+    for (i, level) in permutation.iter().enumerate() {
+        let mut position = order.iter().position(|x| x == level).expect("permutation should only contain levels");
+        while position > i {
+            result = swap_levels(storage, &result, position - 1);
+            order.swap(position - 1, position);
+            position -= 1;
+        }
+    }
+
+    result
+}
+
+/// Returns the relation and meta such that the relational product of the
+/// permuted relation on the permuted set is the permuted relational product,
+/// where the relation rel for meta and permutation are as in [permute].
+pub fn permute_relation(storage: &mut Storage, rel: &LddRef, meta: &LddRef, permutation: &[usize]) -> (Ldd, Ldd) {
+    let positions = meta_positions(storage, meta);
+    let meta_of = |position: usize| positions.get(position).copied().unwrap_or(0);
+
+    // The first level in the relation of every position, and the number of levels in the relation.
+    let mut first_level = Vec::new();
+    let mut length = 0;
+    for position in 0..permutation.len() {
+        first_level.push(length);
+        length += match meta_of(position) {
+            0 => 0,
+            1 | 2 => 1,
+            _ => 2,
+        };
+    }
+
+    // The read and write phases of a position remain adjacent.
+    let mut rel_permutation = Vec::new();
+    let mut read_proj = Vec::new();
+    let mut write_proj = Vec::new();
+    for (i, position) in permutation.iter().enumerate() {
+        match meta_of(*position) {
+            0 => {}
+            1 => {
+                read_proj.push(i as Value);
+                rel_permutation.push(first_level[*position]);
+            }
+            2 => {
+                write_proj.push(i as Value);
+                rel_permutation.push(first_level[*position]);
+            }
+            _ => {
+                read_proj.push(i as Value);
+                write_proj.push(i as Value);
+                rel_permutation.push(first_level[*position]);
+                rel_permutation.push(first_level[*position] + 1);
+            }
+        }
+    }
+    debug_assert_eq!(rel_permutation.len(), length, "meta should not be longer than the permutation");
+
+    let result = permute(storage, rel, &rel_permutation);
+    let result_meta = compute_meta(storage, &read_proj, &write_proj);
+    (result, result_meta)
+}
+
+/// Returns true iff the given slice is a permutation of 0..n, where n is its length.
+fn is_permutation(permutation: &[usize]) -> bool {
+    let mut sorted = permutation.to_vec();
+    sorted.sort_unstable();
+    sorted.iter().enumerate().all(|(i, x)| i == *x)
+}
+
+/// Returns true iff the set contains the vector.
+pub fn element_of(storage: &Storage, vector: &[Value], ldd: &Ldd) -> bool {
+    if vector.is_empty() {
+        ldd == storage.empty_vector()
+    } else if ldd == storage.empty_vector() {
+        false
+    } else {
+        for Data(value, down, _) in iter_right(storage, ldd) {
+            match value.cmp(&vector[0]) {
+                Ordering::Less => {
+                    continue;
+                }
+                Ordering::Equal => {
+                    return element_of(storage, &vector[1..], &down);
+                }
+                Ordering::Greater => {
+                    return false;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Returns true iff every element of a is also an element of b, i.e., a ⊆ b.
+pub fn is_subset(storage: &mut Storage, a: &LddRef, b: &LddRef) -> bool {
+    if a == b || a == storage.empty_set() {
+        true
+    } else if b == storage.empty_set() {
+        false
+    } else {
+        cache_binary_predicate(storage, BinaryPredicate::Subset, a, b, |storage, a, b| {
+            let DataRef(a_value, a_down, a_right) = storage.get_ref(a);
+            let DataRef(b_value, b_down, b_right) = storage.get_ref(b);
+
+            match a_value.cmp(&b_value) {
+                Ordering::Less => false,
+                Ordering::Equal => {
+                    is_subset(storage, &a_down, &b_down) && is_subset(storage, &a_right, &b_right)
+                }
+                Ordering::Greater => is_subset(storage, a, &b_right),
+            }
+        })
+    }
+}
+
+/// Returns true iff a and b have no elements in common, i.e., a ∩ b = ∅.
+pub fn is_disjoint(storage: &mut Storage, a: &LddRef, b: &LddRef) -> bool {
+    if a == storage.empty_set() || b == storage.empty_set() {
+        true
+    } else if a == b {
+        false
+    } else {
+        cache_comm_binary_predicate(storage, BinaryPredicate::Disjoint, a, b, |storage, a, b| {
+            let DataRef(a_value, a_down, a_right) = storage.get_ref(a);
+            let DataRef(b_value, b_down, b_right) = storage.get_ref(b);
+
+            match a_value.cmp(&b_value) {
+                Ordering::Less => is_disjoint(storage, &a_right, b),
+                Ordering::Equal => {
+                    is_disjoint(storage, &a_down, &b_down) && is_disjoint(storage, &a_right, &b_right)
+                }
+                Ordering::Greater => is_disjoint(storage, a, &b_right),
+            }
+        })
+    }
+}
+
+/// Returns true iff a and b have at least one element in common, i.e., a ∩ b ≠ ∅.
+pub fn intersects(storage: &mut Storage, a: &LddRef, b: &LddRef) -> bool {
+    !is_disjoint(storage, a, b)
+}
+
+/// Returns an arbitrary element of the set, or None if the set is empty.
+pub fn pick_one(storage: &Storage, set: &LddRef) -> Option<Vec<Value>> {
+    min_element(storage, set)
+}
+
+/// Returns the lexicographically smallest element of the set, or None if the set is empty.
+pub fn min_element(storage: &Storage, set: &LddRef) -> Option<Vec<Value>> {
+    if set == storage.empty_set() {
+        None
+    } else {
+        let mut result = Vec::new();
+        push_min_element(storage, set, &mut result);
+        Some(result)
+    }
+}
+
+/// Returns the lexicographically largest element of the set, or None if the set is empty.
+pub fn max_element(storage: &Storage, set: &LddRef) -> Option<Vec<Value>> {
+    if set == storage.empty_set() {
+        None
+    } else {
+        let mut result = Vec::new();
+        push_max_element(storage, set, &mut result);
+        Some(result)
+    }
+}
+
+/// Appends the smallest element of the non-empty set to the given vector.
+fn push_min_element(storage: &Storage, set: &LddRef, result: &mut Vec<Value>) {
+    if set != storage.empty_vector() {
+        // The first value of every right chain is the smallest.
+        let DataRef(value, down, _) = storage.get_ref(set);
+        result.push(value);
+        push_min_element(storage, &down, result);
+    }
+}
+
+/// Appends the largest element of the non-empty set to the given vector.
+fn push_max_element(storage: &Storage, set: &LddRef, result: &mut Vec<Value>) {
+    if set != storage.empty_vector() {
+        // The last value of every right chain is the largest.
+        let Data(value, down, right) = storage.get(set);
+        let Data(value, down, _) = iter_right(storage, &right).last().unwrap_or(Data(value, down, right));
+        result.push(value);
+        push_max_element(storage, &down, result);
+    }
+}
+
+/// Returns the number of elements in the set. This can overflow for large
+/// sets, see [len_u128] and [log2_len] for alternatives.
+pub fn len(storage: &mut Storage, set: &LddRef) -> usize {
+    if set == storage.empty_set() {
+        0
+    } else if set == storage.empty_vector() {
+        1
+    } else {
+        cache_unary_function(storage, UnaryFunction::Len, set, |storage, a| {
+            let mut result: usize = 0;
+
+            let mut current = storage.protect(a);
+            while current != *storage.empty_set() {
+                // Progress to the right LDD.
+                let DataRef(_, down, right) = storage.get_ref(&current);
+                result += len(storage, &down);
+                current = storage.protect(&right);
+            }
+
+            result
+        })
+    }
+}
+
+/// Returns the number of elements in the set as a u128, or None if the number
+/// of elements does not fit, see [log2_len] for an alternative.
+pub fn len_u128(storage: &mut Storage, set: &LddRef) -> Option<u128> {
+    if set == storage.empty_set() {
+        Some(0)
+    } else if set == storage.empty_vector() {
+        Some(1)
+    } else {
+        cache_unary_function_u128(storage, UnaryFunctionU128::Len, set, |storage, a| {
+            let mut result: u128 = 0;
+
+            let mut current = storage.protect(a);
+            while current != *storage.empty_set() {
+                // Progress to the right LDD.
+                let DataRef(_, down, right) = storage.get_ref(&current);
+                result = result.checked_add(len_u128(storage, &down)?)?;
+                current = storage.protect(&right);
+            }
+
+            Some(result)
+        })
+    }
+}
+
+/// Returns the base two logarithm of the number of elements in the set, which
+/// is negative infinity for the empty set.
+///
+/// The logarithm is computed for every subtree separately, which means that
+/// the result is approximate, but it cannot overflow.
+pub fn log2_len(storage: &mut Storage, set: &LddRef) -> f64 {
+    if set == storage.empty_set() {
+        f64::NEG_INFINITY
+    } else if set == storage.empty_vector() {
+        0.0
+    } else {
+        cache_unary_function_f64(storage, UnaryFunctionF64::Log2Len, set, |storage, a| {
+            let mut counts: Vec<f64> = Vec::new();
+
+            let mut current = storage.protect(a);
+            while current != *storage.empty_set() {
+                // Progress to the right LDD.
+                let DataRef(_, down, right) = storage.get_ref(&current);
+                counts.push(log2_len(storage, &down));
+                current = storage.protect(&right);
+            }
+
+            // Computes log2(2^c_0 + ... + 2^c_n) relative to the largest count to avoid overflow.
+            let max = counts.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let sum: f64 = counts.iter().map(|count| (count - max).exp2()).sum();
+            max + sum.log2()
+        })
+    }
+}
+
+/// Returns the index of the vector in the lexicographic order of the
+/// elements of the set, or None if the vector is not an element of the set or
+/// its index does not fit in a u128.
+///
+/// This is the inverse of [unrank] and uses the cached [len_u128] of every subtree.
+pub fn rank(storage: &mut Storage, set: &LddRef, vector: &[Value]) -> Option<u128> {
+    let mut result: u128 = 0;
+
+    let mut current = storage.protect(set);
+    for element in vector {
+        // Count the elements of all nodes in the right chain that precede the value.
+        loop {
+            if current == *storage.empty_set() || current == *storage.empty_vector() {
+                return None;
+            }
+
+            let DataRef(value, down, right) = storage.get_ref(&current);
+            match value.cmp(element) {
+                Ordering::Less => {
+                    result = result.checked_add(len_u128(storage, &down)?)?;
+                    current = storage.protect(&right);
+                }
+                Ordering::Equal => {
+                    current = storage.protect(&down);
+                    break;
+                }
+                Ordering::Greater => {
+                    return None;
+                }
+            }
+        }
+    }
+
+    if current == *storage.empty_vector() {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Returns the vector with the given index in the lexicographic order of the
+/// elements of the set, or None if the index is not smaller than len(set).
+///
+/// This is the inverse of [rank] and uses the cached [len_u128] of every subtree.
+pub fn unrank(storage: &mut Storage, set: &LddRef, index: u128) -> Option<Vec<Value>> {
+    if set == storage.empty_set() || len_u128(storage, set).map_or(false, |len| index >= len) {
+        return None;
+    }
+
+    let mut index = index;
+    let mut result = Vec::new();
+
+    let mut current = storage.protect(set);
+    while current != *storage.empty_vector() {
+        // Find the node in the right chain that contains the element with the given index.
+        loop {
+            let DataRef(value, down, right) = storage.get_ref(&current);
+
+            match len_u128(storage, &down) {
+                Some(count) if index >= count => {
+                    index -= count;
+                    current = storage.protect(&right);
+                }
+                _ => {
+                    // The index is in this subtree, which is also the case when its number of elements does not fit.
+                    result.push(value);
+                    current = storage.protect(&down);
+                    break;
+                }
+            }
+        }
+    }
+
+    Some(result)
+}
+
+/// Returns an element of the set drawn uniformly at random, or None if the set is empty.
+///
+/// Every branch is weighted by the number of elements below it, which are
+/// obtained from the cached [len_u128] of every subtree. When the number of
+/// elements does not fit in a u128 the branches are weighted by [log2_len]
+/// instead, which means that the distribution is approximately uniform.
+pub fn sample(storage: &mut Storage, set: &LddRef, rng: &mut impl Rng) -> Option<Vec<Value>> {
+    match len_u128(storage, set) {
+        Some(0) => None,
+        Some(total) => {
+            let index = rng.gen_range(0..total);
+            unrank(storage, set, index)
+        }
+        None => {
+            let total = log2_len(storage, set);
+            let mut point: f64 = rng.gen();
+
+            // Find the branch that contains the point, where the last branch also takes the rounding errors.
+            let mut current = storage.protect(set);
+            loop {
+                let DataRef(value, down, right) = storage.get_ref(&current);
+                let weight = (log2_len(storage, &down) - total).exp2();
+
+                if point < weight || right == *storage.empty_set() {
+                    let mut result = vec![value];
+                    result.extend(sample(storage, &down, rng)?);
+                    return Some(result);
+                }
+
+                point -= weight;
+                current = storage.protect(&right);
+            }
+        }
+    }
+}
+
+/// Returns the given amount of elements of the set, where every element is
+/// drawn uniformly at random (with replacement). The result is empty if the
+/// set is empty.
+pub fn sample_many(storage: &mut Storage, set: &LddRef, rng: &mut impl Rng, amount: usize) -> Vec<Vec<Value>> {
+    let mut result = Vec::new();
+    for _ in 0..amount {
+        match sample(storage, set, rng) {
+            Some(vector) => result.push(vector),
+            None => break,
+        }
+    }
+
+    result
+}
+
+/// Returns the height of the LDD tree.
+pub fn height(storage: &Storage, ldd: &LddRef) -> u64 {
+    if ldd == storage.empty_set() || ldd == storage.empty_vector() {
+        0
+    } else {
+        // Since all children have the same height we only have to look at the down node.
+        let DataRef(_, down, _) = storage.get_ref(ldd);
+
+        height(storage, &down) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fmt_node;
+    use crate::test_utility::*;
+
+    use rand::Rng;
+    use std::collections::HashSet;
+    use std::ops::Sub;
+
+    // Compare the LDD element_of implementation for random inputs.
     #[test]
     fn random_element_of() {
         let mut storage = Storage::new();
@@ -499,6 +1940,34 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    // Test the zip function against the separate union and minus functions.
+    #[test]
+    fn random_zip() {
+        let mut storage = Storage::new();
+
+        let set_a = random_vector_set(32, 10, 10);
+        let set_b = {
+            let mut result = random_vector_set(32, 10, 10);
+
+            // To ensure some overlap (which is unlikely) we insert some elements of a into b.
+            let mut it = set_a.iter();
+            for _ in 0..16 {
+                result.insert(it.next().unwrap().clone());
+            }
+
+            result
+        };
+
+        let a = from_iter(&mut storage, set_a.iter());
+        let b = from_iter(&mut storage, set_b.iter());
+        let (union_result, minus_result) = zip(&mut storage, &a, &b);
+
+        let expected_union = from_iter(&mut storage, set_a.union(&set_b));
+        let expected_minus = from_iter(&mut storage, set_b.sub(&set_a).iter());
+        assert_eq!(union_result, expected_union);
+        assert_eq!(minus_result, expected_minus);
+    }
+
     #[test]
     fn random_merge() {
         let mut storage = Storage::new();
@@ -507,7 +1976,7 @@ mod tests {
         let set_b = random_vector_set(32, 10, 10);
 
         // Compute the interleave explicitly.
-        fn interleave(a: &Vec<u32>, b: &Vec<u32>) -> Vec<u32> {
+        fn interleave(a: &[u32], b: &[u32]) -> Vec<u32> {
             let mut result = vec![];
 
             let mut iter = b.iter();
@@ -554,6 +2023,118 @@ mod tests {
         );
     }
 
+    // Test the union_vector and minus_vector functions with random inputs.
+    #[test]
+    fn random_union_minus_vector() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 10, 10);
+        let ldd = from_iter(&mut storage, set.iter());
+
+        // Insert vectors that are most likely not in the set.
+        let mut expected_set = set.clone();
+        let mut result = ldd.clone();
+        for vector in random_vector_set(16, 10, 10) {
+            result = union_vector(&mut storage, &result, &vector);
+            expected_set.insert(vector);
+        }
+
+        let expected = from_iter(&mut storage, expected_set.iter());
+        assert_eq!(result, expected, "inserting vectors does not match the vector set.");
+
+        // Remove both vectors in the set and vectors that are most likely not in the set.
+        let mut removed: Vec<Vec<Value>> = set.iter().take(16).cloned().collect();
+        removed.extend(random_vector_set(16, 10, 10));
+        for vector in &removed {
+            result = minus_vector(&mut storage, &result, vector);
+            expected_set.remove(vector);
+        }
+
+        let expected = from_iter(&mut storage, expected_set.iter());
+        assert_eq!(result, expected, "removing vectors does not match the vector set.");
+    }
+
+    // Compare the HashSet implementation of is_subset with the LDD implementation for random inputs.
+    #[test]
+    fn random_is_subset() {
+        let mut storage = Storage::new();
+
+        let set_a = random_vector_set(32, 10, 10);
+        let set_b = {
+            let mut result = random_vector_set(32, 10, 10);
+
+            // Make b a superset of a with high probability.
+            for element in set_a.iter().skip(rand::thread_rng().gen_range(0..2)) {
+                result.insert(element.clone());
+            }
+
+            result
+        };
+
+        let a = from_iter(&mut storage, set_a.iter());
+        let b = from_iter(&mut storage, set_b.iter());
+
+        assert_eq!(is_subset(&mut storage, &a, &b), set_a.is_subset(&set_b));
+        assert_eq!(is_subset(&mut storage, &b, &a), set_b.is_subset(&set_a));
+        assert!(is_subset(&mut storage, &a, &a));
+    }
+
+    // Compare the HashSet implementation of is_disjoint with the LDD implementation for random inputs.
+    #[test]
+    fn random_is_disjoint() {
+        let mut storage = Storage::new();
+
+        let set_a = random_vector_set(32, 10, 10);
+        let set_b = {
+            let mut result = random_vector_set(32, 10, 10);
+
+            // Make the sets overlap in half of the cases.
+            if rand::thread_rng().gen_bool(0.5) {
+                result.insert(set_a.iter().next().unwrap().clone());
+            }
+
+            result
+        };
+
+        let a = from_iter(&mut storage, set_a.iter());
+        let b = from_iter(&mut storage, set_b.iter());
+
+        assert_eq!(is_disjoint(&mut storage, &a, &b), set_a.is_disjoint(&set_b));
+        assert_eq!(intersects(&mut storage, &b, &a), !set_b.is_disjoint(&set_a));
+    }
+
+    // Test the pick_one, min_element and max_element functions with random inputs.
+    #[test]
+    fn random_pick_one() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 10, 10);
+        let ldd = from_iter(&mut storage, set.iter());
+
+        let element = pick_one(&storage, &ldd).unwrap();
+        assert!(set.contains(&element), "Picked element should be in the set.");
+
+        assert_eq!(min_element(&storage, &ldd).as_ref(), set.iter().min());
+        assert_eq!(max_element(&storage, &ldd).as_ref(), set.iter().max());
+
+        assert_eq!(pick_one(&storage, storage.empty_set()), None);
+        assert_eq!(min_element(&storage, storage.empty_vector()), Some(vec![]));
+    }
+
+    // Test that max_element does not recurse along a long right chain.
+    #[test]
+    fn test_max_element_long_chain() {
+        let mut storage = Storage::new();
+
+        let mut ldd = storage.empty_set().clone();
+        let empty_vector = storage.empty_vector().clone();
+        for value in (0..100000).rev() {
+            ldd = storage.insert(value, &empty_vector, &ldd);
+        }
+
+        assert_eq!(max_element(&storage, &ldd), Some(vec![99999]));
+    }
+
     // Test the len function with random inputs.
     #[test]
     fn random_len() {
@@ -569,9 +2150,134 @@ mod tests {
         );
     }
 
-    // Test the minus function with random inputs.
+    // Test the len_u128 and log2_len functions with random inputs.
+    #[test]
+    fn random_len_u128() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 10, 10);
+        let ldd = from_iter(&mut storage, set.iter());
+
+        assert_eq!(len_u128(&mut storage, &ldd), Some(set.len() as u128));
+        assert!((log2_len(&mut storage, &ldd) - (set.len() as f64).log2()).abs() < 1e-9);
+
+        // The product of 40 levels with 8 values each has 2^120 elements, which does not fit in a usize.
+        let mut large = storage.empty_vector().clone();
+        for _ in 0..40 {
+            let mut level = storage.empty_set().clone();
+            for value in (0..8).rev() {
+                level = storage.insert(value, &large, &level);
+            }
+            large = level;
+        }
+
+        assert_eq!(len_u128(&mut storage, &large), Some(1u128 << 120));
+        assert!((log2_len(&mut storage, &large) - 120.0).abs() < 1e-9);
+
+        // With four more levels there are 2^132 elements, which does not fit in a u128.
+        for _ in 0..4 {
+            let mut level = storage.empty_set().clone();
+            for value in (0..8).rev() {
+                level = storage.insert(value, &large, &level);
+            }
+            large = level;
+        }
+
+        assert_eq!(len_u128(&mut storage, &large), None);
+        assert!((log2_len(&mut storage, &large) - 132.0).abs() < 1e-9);
+
+        // The first element still has a rank, but the last element does not.
+        assert_eq!(unrank(&mut storage, &large, 0), Some(vec![0; 44]));
+        assert_eq!(rank(&mut storage, &large, &[0; 44]), Some(0));
+        assert_eq!(rank(&mut storage, &large, &[7; 44]), None);
+
+        let vector = sample(&mut storage, &large, &mut rand::thread_rng()).expect("the set is not empty");
+        assert!(element_of(&storage, &vector, &large));
+
+        let empty_set = storage.empty_set().clone();
+        assert_eq!(log2_len(&mut storage, &empty_set), f64::NEG_INFINITY);
+    }
+
+    // Test the rank and unrank functions with random inputs.
+    #[test]
+    fn random_rank() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 10, 10);
+        let ldd = from_iter(&mut storage, set.iter());
+
+        let mut sorted: Vec<&Vec<Value>> = set.iter().collect();
+        sorted.sort();
+
+        for (index, vector) in sorted.iter().enumerate() {
+            assert_eq!(rank(&mut storage, &ldd, vector), Some(index as u128));
+            assert_eq!(unrank(&mut storage, &ldd, index as u128).as_ref(), Some(*vector));
+        }
+
+        // Indices outside of the set have no vector.
+        assert_eq!(unrank(&mut storage, &ldd, set.len() as u128), None);
+        assert_eq!(unrank(&mut storage, &ldd, u128::MAX), None);
+
+        // Vectors that are not in the set have no rank.
+        for vector in random_vector_set(10, 10, 10) {
+            if !set.contains(&vector) {
+                assert_eq!(rank(&mut storage, &ldd, &vector), None);
+            }
+        }
+        assert_eq!(rank(&mut storage, &ldd, &random_vector(5, 10)), None);
+    }
+
+    // Test the sample function with random inputs.
+    #[test]
+    fn random_sample() {
+        let mut storage = Storage::new();
+        let mut rng = rand::thread_rng();
+
+        let set = random_vector_set(8, 10, 10);
+        let ldd = from_iter(&mut storage, set.iter());
+
+        // With this many samples every element should be drawn at least once.
+        let samples = sample_many(&mut storage, &ldd, &mut rng, 1000);
+        assert_eq!(samples.len(), 1000);
+
+        let drawn: HashSet<Vec<Value>> = samples.into_iter().collect();
+        assert_eq!(drawn, set, "Samples should be exactly the elements of the set.");
+
+        let empty_set = storage.empty_set().clone();
+        assert_eq!(sample(&mut storage, &empty_set, &mut rng), None);
+    }
+
+    // Test the minus function with random inputs.
+    #[test]
+    fn random_minus() {
+        let mut storage = Storage::new();
+
+        let set_a = random_vector_set(32, 10, 10);
+        let set_b = {
+            let mut result = random_vector_set(32, 10, 10);
+
+            // To ensure some overlap (which is unlikely) we insert some elements of a into b.
+            let mut it = set_a.iter();
+            for _ in 0..16 {
+                result.insert(it.next().unwrap().clone());
+            }
+
+            result
+        };
+
+        let expected_result = set_a.sub(&set_b);
+
+        let a = from_iter(&mut storage, set_a.iter());
+        let b = from_iter(&mut storage, set_b.iter());
+        let result = minus(&mut storage, &a, &b);
+
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected);
+    }
+
+    // Compare the HashSet implementation of intersection with the LDD intersect implementation for random inputs.
     #[test]
-    fn random_minus() {
+    fn random_intersect() {
         let mut storage = Storage::new();
 
         let set_a = random_vector_set(32, 10, 10);
@@ -587,13 +2293,12 @@ mod tests {
             result
         };
 
-        let expected_result = set_a.sub(&set_b);
+        let expected = from_iter(&mut storage, set_a.intersection(&set_b));
 
         let a = from_iter(&mut storage, set_a.iter());
         let b = from_iter(&mut storage, set_b.iter());
-        let result = minus(&mut storage, &a, &b);
+        let result = intersect(&mut storage, &a, &b);
 
-        let expected = from_iter(&mut storage, expected_result.iter());
         assert_eq!(result, expected);
     }
 
@@ -740,6 +2445,483 @@ mod tests {
         }
     }
 
+    #[test]
+    fn random_relational_prev() {
+        let mut storage = Storage::new();
+
+        let universe = random_vector_set(32, 10, 10);
+        let relation = random_vector_set(32, 4, 10);
+
+        // Pick arbitrary read and write parameters in order.
+        let read_proj = random_sorted_vector(2, 9);
+        let write_proj = random_sorted_vector(2, 9);
+
+        // Determine the positions of the read and write parameters in the relation.
+        let (read_rel_proj, write_rel_proj) = relation_layout(&read_proj, &write_proj);
+
+        let uni = from_iter(&mut storage, universe.iter());
+        let rel = from_iter(&mut storage, relation.iter());
+        let meta = compute_meta(&mut storage, &read_proj, &write_proj);
+
+        // Ensure that some successors of the universe are part of the set (which is unlikely otherwise).
+        let successors = relational_product(&mut storage, &uni, &rel, &meta);
+        let set = {
+            let mut result = random_vector_set(32, 10, 10);
+            for vector in iter(&storage, &successors).take(16) {
+                result.insert(vector);
+            }
+
+            result
+        };
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let result = relational_prev(&mut storage, &ldd, &rel, &meta, &uni);
+
+        // Compute relational_prev(R, S, U, read_proj, write_proj) = { x in U | project(x, read_proj) = x' and (x', y') in R and x[write_proj := y'] in S }
+        let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+        for x in universe.iter() {
+            'next: for rel in relation.iter() {
+                let x_prime = project_vector(rel, &read_rel_proj);
+                let y_prime = project_vector(rel, &write_rel_proj);
+
+                // Ensure that project(x, read_proj) = x'
+                for (i, r) in read_proj.iter().enumerate() {
+                    if x[*r as usize] != x_prime[i] {
+                        continue 'next;
+                    }
+                }
+
+                // Compute x[write_proj := y']
+                let mut value = x.clone();
+                for (i, w) in write_proj.iter().enumerate() {
+                    value[*w as usize] = y_prime[i];
+                }
+
+                if set.contains(&value) {
+                    expected_result.insert(x.clone());
+                }
+            }
+        }
+
+        let expected = from_iter(&mut storage, expected_result.iter());
+        print_differences(&storage, &result, &expected);
+        assert_eq!(result, expected, "pre-image does not match the explicit pre-image.");
+    }
+
+    // Test the relational product and its pre-image for relations with copy nodes.
+    #[test]
+    fn random_relational_product_copy() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 8, 4);
+        let universe = {
+            let mut result = random_vector_set(32, 8, 4);
+            result.extend(set.iter().take(16).cloned());
+            result
+        };
+
+        // The meta is <0, 1, 0, 0, 3, 4, 0, 2>, which contains both write phases.
+        let read_proj = vec![1, 4];
+        let write_proj = vec![4, 7];
+        let relation = random_copy_relation(32, &read_proj, &write_proj, 4);
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let uni = from_iter(&mut storage, universe.iter());
+        let rel = from_iter(&mut storage, relation.iter());
+        let meta = compute_meta(&mut storage, &read_proj, &write_proj);
+
+        let successors = |x: &Vec<Value>| explicit_successors(x, &relation, &read_proj, &write_proj);
+
+        let expected_product: HashSet<Vec<Value>> = set.iter().flat_map(successors).collect();
+        let expected = from_iter(&mut storage, expected_product.iter());
+        let result = relational_product(&mut storage, &ldd, &rel, &meta);
+        print_differences(&storage, &result, &expected);
+        assert_eq!(result, expected, "relational product does not match the explicit successors.");
+
+        let expected_prev: HashSet<Vec<Value>> = universe
+            .iter()
+            .filter(|x| successors(x).iter().any(|y| set.contains(y)))
+            .cloned()
+            .collect();
+        let expected = from_iter(&mut storage, expected_prev.iter());
+        let result = relational_prev(&mut storage, &ldd, &rel, &meta, &uni);
+        print_differences(&storage, &result, &expected);
+        assert_eq!(result, expected, "pre-image does not match the explicit pre-image.");
+    }
+
+    // Test the extend_relation function by combining two groups with random relations.
+    #[test]
+    fn random_extend_relation() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 8, 4);
+        let universe = {
+            let mut result = random_vector_set(32, 8, 4);
+            result.extend(set.iter().take(16).cloned());
+            result
+        };
+
+        // The groups as (read_proj, write_proj), where the combined meta extends
+        // positions from 0, 1 and 2 to every wider meta.
+        let groups = [
+            (vec![1, 4], vec![4, 7]),
+            (vec![0, 1, 6], vec![1, 5, 7]),
+        ];
+        let new_meta = compute_meta(&mut storage, &[0, 1, 4, 6, 7], &[1, 4, 5, 7]);
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let uni = from_iter(&mut storage, universe.iter());
+
+        let mut combined = storage.empty_set().clone();
+        let mut expected_product: HashSet<Vec<Value>> = HashSet::new();
+        let mut expected_prev: HashSet<Vec<Value>> = HashSet::new();
+        for (read_proj, write_proj) in &groups {
+            let relation = random_copy_relation(32, read_proj, write_proj, 4);
+
+            let rel = from_iter(&mut storage, relation.iter());
+            let meta = compute_meta(&mut storage, read_proj, write_proj);
+            let extended = extend_relation(&mut storage, &rel, &meta, &new_meta);
+
+            let result = relational_product(&mut storage, &ldd, &extended, &new_meta);
+            let expected = relational_product(&mut storage, &ldd, &rel, &meta);
+            assert_eq!(result, expected, "extended relation does not have the same successors.");
+
+            let result = relational_prev(&mut storage, &ldd, &extended, &new_meta, &uni);
+            let expected = relational_prev(&mut storage, &ldd, &rel, &meta, &uni);
+            assert_eq!(result, expected, "extended relation does not have the same pre-image.");
+
+            combined = union(&mut storage, &combined, &extended);
+
+            let successors = |x: &Vec<Value>| explicit_successors(x, &relation, read_proj, write_proj);
+
+            expected_product.extend(set.iter().flat_map(successors));
+            expected_prev.extend(
+                universe
+                    .iter()
+                    .filter(|x| successors(x).iter().any(|y| set.contains(y)))
+                    .cloned(),
+            );
+        }
+
+        let expected = from_iter(&mut storage, expected_product.iter());
+        let result = relational_product(&mut storage, &ldd, &combined, &new_meta);
+        print_differences(&storage, &result, &expected);
+        assert_eq!(result, expected, "relational product of the combined groups does not match the explicit successors.");
+
+        let expected = from_iter(&mut storage, expected_prev.iter());
+        let result = relational_prev(&mut storage, &ldd, &combined, &new_meta, &uni);
+        print_differences(&storage, &result, &expected);
+        assert_eq!(result, expected, "pre-image of the combined groups does not match the explicit pre-image.");
+    }
+
+    // Test the compose function against the explicit composition of two relations.
+    #[test]
+    fn random_compose() {
+        let mut storage = Storage::new();
+
+        let (read1, write1) = (vec![0, 1], vec![1, 2]);
+        let (read2, write2) = (vec![2], vec![0, 3]);
+        let relation1 = random_copy_relation(16, &read1, &write1, 3);
+        let relation2 = random_copy_relation(16, &read2, &write2, 3);
+
+        let rel1 = from_iter(&mut storage, relation1.iter());
+        let rel2 = from_iter(&mut storage, relation2.iter());
+        let meta1 = compute_meta(&mut storage, &read1, &write1);
+        let meta2 = compute_meta(&mut storage, &read2, &write2);
+        let (rel, meta) = compose(&mut storage, &rel1, &meta1, &rel2, &meta2);
+
+        // Compare the successors of every vector with the explicit composition.
+        for x in all_vectors(4, 3) {
+            let expected_successors: HashSet<Vec<Value>> = explicit_successors(&x, &relation1, &read1, &write1)
+                .iter()
+                .flat_map(|y| explicit_successors(y, &relation2, &read2, &write2))
+                .collect();
+
+            let single = singleton(&mut storage, &x);
+            let result = relational_product(&mut storage, &single, &rel, &meta);
+            let expected = from_iter(&mut storage, expected_successors.iter());
+            print_differences(&storage, &result, &expected);
+            assert_eq!(result, expected, "composed relation does not match the explicit composition for {:?}.", x);
+        }
+    }
+
+    // Test the transitive_closure function against the explicit transitive closure of a relation.
+    #[test]
+    fn random_transitive_closure() {
+        let mut storage = Storage::new();
+
+        let (read_proj, write_proj) = (vec![0, 1], vec![1, 3]);
+        let relation = random_copy_relation(16, &read_proj, &write_proj, 3);
+
+        let rel = from_iter(&mut storage, relation.iter());
+        let meta = compute_meta(&mut storage, &read_proj, &write_proj);
+        let (closure, closure_meta) = transitive_closure(&mut storage, &rel, &meta);
+
+        // Compare the successors of every vector with the vectors reachable in one or more steps.
+        for x in all_vectors(4, 3) {
+            let mut expected_successors = explicit_successors(&x, &relation, &read_proj, &write_proj);
+            let mut todo: Vec<Vec<Value>> = expected_successors.iter().cloned().collect();
+            while let Some(y) = todo.pop() {
+                for z in explicit_successors(&y, &relation, &read_proj, &write_proj) {
+                    if expected_successors.insert(z.clone()) {
+                        todo.push(z);
+                    }
+                }
+            }
+
+            let single = singleton(&mut storage, &x);
+            let result = relational_product(&mut storage, &single, &closure, &closure_meta);
+            let expected = from_iter(&mut storage, expected_successors.iter());
+            print_differences(&storage, &result, &expected);
+            assert_eq!(result, expected, "transitive closure does not match the explicit closure for {:?}.", x);
+        }
+    }
+
+    // Test the inverse function by comparing its relational product with the pre-image.
+    #[test]
+    fn random_inverse() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 5, 3);
+        let universe = random_vector_set(64, 5, 3);
+
+        // The meta is <1, 0, 3, 4, 3, 4, 2>, where the write-only level only contains copies.
+        let (read_proj, write_proj) = (vec![0, 2, 3], vec![2, 3, 4]);
+        let (read_rel_proj, write_rel_proj) = relation_layout(&read_proj, &write_proj);
+
+        // Replace some of the values by copies, where a read copy is always followed by a write copy.
+        let relation: HashSet<Vec<Value>> = random_vector_set(32, 6, 3)
+            .into_iter()
+            .map(|mut vector| {
+                let mut rng = rand::thread_rng();
+                if rng.gen_bool(0.2) {
+                    vector[read_rel_proj[0] as usize] = COPY;
+                }
+
+                for (read, write) in read_rel_proj[1..].iter().zip(&write_rel_proj) {
+                    if rng.gen_bool(0.3) {
+                        vector[*write as usize] = COPY;
+                        if rng.gen_bool(0.5) {
+                            vector[*read as usize] = COPY;
+                        }
+                    }
+                }
+
+                vector[write_rel_proj[2] as usize] = COPY;
+                vector
+            })
+            .collect();
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let uni = from_iter(&mut storage, universe.iter());
+        let rel = from_iter(&mut storage, relation.iter());
+        let meta = compute_meta(&mut storage, &read_proj, &write_proj);
+
+        let inverse_rel = inverse(&mut storage, &rel, &meta).expect("the relation should be invertible");
+        assert_eq!(inverse(&mut storage, &inverse_rel, &meta), Some(rel.clone()), "the inverse of the inverse should be the relation itself.");
+
+        let expected_prev: HashSet<Vec<Value>> = universe
+            .iter()
+            .filter(|x| explicit_successors(x, &relation, &read_proj, &write_proj).iter().any(|y| set.contains(y)))
+            .cloned()
+            .collect();
+        let expected = from_iter(&mut storage, expected_prev.iter());
+
+        let result = relational_prev(&mut storage, &ldd, &rel, &meta, &uni);
+        assert_eq!(result, expected, "pre-image does not match the explicit pre-image.");
+
+        let result = relational_product(&mut storage, &ldd, &inverse_rel, &meta);
+        let result = intersect(&mut storage, &result, &uni);
+        print_differences(&storage, &result, &expected);
+        assert_eq!(result, expected, "relational product of the inverse does not match the explicit pre-image.");
+    }
+
+    // Test that the inverse is only defined when every written value can be inverted.
+    #[test]
+    fn test_inverse_meta_two() {
+        let mut storage = Storage::new();
+
+        // The meta is <1, 2>, where the write-only level only contains copies.
+        let meta = compute_meta(&mut storage, &[0], &[1]);
+        let rel = from_iter(&mut storage, [vec![0, COPY], vec![1, COPY]].iter());
+        assert_eq!(inverse(&mut storage, &rel, &meta), Some(rel.clone()), "copies are their own inverse.");
+
+        // Writing a value at the write-only level forgets the value that was there before.
+        let rel = from_iter(&mut storage, [vec![0, COPY], vec![1, 2]].iter());
+        assert_eq!(inverse(&mut storage, &rel, &meta), None);
+
+        // The meta is <3, 4>, where a copy node that is read is followed by a written value.
+        let meta = compute_meta(&mut storage, &[0], &[0]);
+        let rel = from_iter(&mut storage, [vec![0, 1], vec![COPY, 2]].iter());
+        assert_eq!(inverse(&mut storage, &rel, &meta), None);
+    }
+
+    // Test the concat function with random inputs.
+    #[test]
+    fn random_concat() {
+        let mut storage = Storage::new();
+
+        let set_a = random_vector_set(32, 4, 10);
+        let set_b = random_vector_set(32, 3, 10);
+
+        let a = from_iter(&mut storage, set_a.iter());
+        let b = from_iter(&mut storage, set_b.iter());
+        let result = concat(&mut storage, &a, &b);
+
+        let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+        for x in &set_a {
+            for y in &set_b {
+                expected_result.insert(x.iter().chain(y).cloned().collect());
+            }
+        }
+
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "concatenation does not match the concatenated vectors.");
+    }
+
+    // Test the cube function with random inputs.
+    #[test]
+    fn random_cube() {
+        let mut storage = Storage::new();
+
+        let values: Vec<Vec<Value>> = (0..4).map(|_| random_vector(3, 5)).collect();
+        let result = cube(&mut storage, &values);
+
+        let expected_result: HashSet<Vec<Value>> = all_vectors(4, 5)
+            .into_iter()
+            .filter(|x| x.iter().zip(&values).all(|(value, level)| level.contains(value)))
+            .collect();
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "cube does not match the cartesian product.");
+
+        assert_eq!(cube(&mut storage, &[vec![1, 2], vec![], vec![3]]), *storage.empty_set());
+        assert_eq!(cube(&mut storage, &[]), *storage.empty_vector());
+    }
+
+    // Test the insert_level function with random inputs.
+    #[test]
+    fn random_insert_level() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 6, 10);
+        let position = rand::thread_rng().gen_range(0..=6);
+        let values = random_vector(3, 10);
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let result = insert_level(&mut storage, &ldd, position, &values);
+
+        let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+        for x in &set {
+            for value in &values {
+                let mut vector = x.clone();
+                vector.insert(position, *value);
+                expected_result.insert(vector);
+            }
+        }
+
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "inserting a level at position {} does not match.", position);
+    }
+
+    // Test the remove_level function with random inputs.
+    #[test]
+    fn random_remove_level() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 6, 10);
+        let position = rand::thread_rng().gen_range(0..6);
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let result = remove_level(&mut storage, &ldd, position);
+
+        let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+        for x in &set {
+            let mut vector = x.clone();
+            vector.remove(position);
+            expected_result.insert(vector);
+        }
+
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "removing the level at position {} does not match.", position);
+    }
+
+    // Test the duplicate_level function with random inputs.
+    #[test]
+    fn random_duplicate_level() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 6, 10);
+        let position = rand::thread_rng().gen_range(0..6);
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let result = duplicate_level(&mut storage, &ldd, position);
+
+        let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+        for x in &set {
+            let mut vector = x.clone();
+            vector.insert(position, x[position]);
+            expected_result.insert(vector);
+        }
+
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "duplicating the level at position {} does not match.", position);
+    }
+
+    // Test the permute function with random inputs.
+    #[test]
+    fn random_permute() {
+        use rand::seq::SliceRandom;
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 6, 10);
+        let mut permutation: Vec<usize> = (0..6).collect();
+        permutation.shuffle(&mut rand::thread_rng());
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let result = permute(&mut storage, &ldd, &permutation);
+
+        let expected_result: HashSet<Vec<Value>> = set
+            .iter()
+            .map(|x| permutation.iter().map(|i| x[*i]).collect())
+            .collect();
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "permutation {:?} does not match.", permutation);
+    }
+
+    // Test the permute_relation function by comparing the relational product before and after permuting.
+    #[test]
+    fn random_permute_relation() {
+        use rand::seq::SliceRandom;
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 6, 4);
+        let universe = random_vector_set(64, 6, 4);
+        let mut permutation: Vec<usize> = (0..6).collect();
+        permutation.shuffle(&mut rand::thread_rng());
+
+        let (read_proj, write_proj) = (vec![0, 2, 3], vec![1, 3, 4]);
+        let relation = random_copy_relation(16, &read_proj, &write_proj, 4);
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let uni = from_iter(&mut storage, universe.iter());
+        let rel = from_iter(&mut storage, relation.iter());
+        let meta = compute_meta(&mut storage, &read_proj, &write_proj);
+        let (permuted_rel, permuted_meta) = permute_relation(&mut storage, &rel, &meta, &permutation);
+
+        let permuted_set = permute(&mut storage, &ldd, &permutation);
+        let permuted_uni = permute(&mut storage, &uni, &permutation);
+
+        let expected = relational_product(&mut storage, &ldd, &rel, &meta);
+        let expected = permute(&mut storage, &expected, &permutation);
+        let result = relational_product(&mut storage, &permuted_set, &permuted_rel, &permuted_meta);
+        assert_eq!(result, expected, "permutation {:?} of the relation does not match.", permutation);
+
+        let expected = relational_prev(&mut storage, &ldd, &rel, &meta, &uni);
+        let expected = permute(&mut storage, &expected, &permutation);
+        let result = relational_prev(&mut storage, &permuted_set, &permuted_rel, &permuted_meta, &permuted_uni);
+        assert_eq!(result, expected, "permutation {:?} of the pre-image does not match.", permutation);
+    }
+
     // Test the project function with random inputs.
     #[test]
     fn random_project() {
@@ -764,6 +2946,83 @@ mod tests {
         );
     }
 
+    // Test the project_minus function with random inputs.
+    #[test]
+    fn random_project_minus() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 10, 10);
+        let proj = random_sorted_vector(4, 9);
+
+        // To ensure some overlap (which is unlikely) we insert some projected elements into avoid.
+        let avoid = {
+            let mut result = random_vector_set(32, 4, 10);
+            for element in set.iter().take(16) {
+                result.insert(project_vector(element, &proj));
+            }
+
+            result
+        };
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let avoid_ldd = from_iter(&mut storage, avoid.iter());
+        let proj_ldd = compute_proj(&mut storage, &proj);
+        let result = project_minus(&mut storage, &ldd, &proj_ldd, &avoid_ldd);
+
+        // Compute a naive projection on the vector set.
+        let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+        for element in &set {
+            let projected = project_vector(element, &proj);
+            if !avoid.contains(&projected) {
+                expected_result.insert(projected);
+            }
+        }
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "projected result does not match vector projection minus avoid.");
+    }
+
+    // Test the match_pattern function with random inputs.
+    #[test]
+    fn random_match_pattern() {
+        let mut storage = Storage::new();
+
+        let set = random_vector_set(32, 10, 10);
+        let proj = random_sorted_vector(4, 9);
+
+        // To ensure that some vectors match (which is unlikely) we insert some projected elements into the pattern.
+        let pattern = {
+            let mut result = random_vector_set(32, 4, 10);
+            for element in set.iter().take(16) {
+                result.insert(project_vector(element, &proj));
+            }
+
+            result
+        };
+
+        let ldd = from_iter(&mut storage, set.iter());
+        let pattern_ldd = from_iter(&mut storage, pattern.iter());
+        let proj_ldd = compute_proj(&mut storage, &proj);
+        let result = match_pattern(&mut storage, &ldd, &pattern_ldd, &proj_ldd);
+
+        // Compute a naive filter on the vector set.
+        let mut expected_result: HashSet<Vec<Value>> = HashSet::new();
+        for element in &set {
+            if pattern.contains(&project_vector(element, &proj)) {
+                expected_result.insert(element.clone());
+            }
+        }
+        let expected = from_iter(&mut storage, expected_result.iter());
+        assert_eq!(result, expected, "matched result does not match vector filter.");
+
+        // No vector matches the empty pattern, even when no level is constrained.
+        let empty_set = storage.empty_set().clone();
+        let empty_vector = storage.empty_vector().clone();
+        let empty_proj = compute_proj(&mut storage, &[]);
+        assert_eq!(match_pattern(&mut storage, &ldd, &empty_set, &empty_proj), empty_set, "no vector should match the empty pattern.");
+        assert_eq!(match_pattern(&mut storage, &ldd, &empty_set, &proj_ldd), empty_set, "no vector should match the empty pattern.");
+        assert_eq!(match_pattern(&mut storage, &ldd, &empty_vector, &empty_proj), ldd, "every vector should match the empty vector.");
+    }
+
     // Test the append function with random inputs.
     #[test]
     fn random_append() {
@@ -0,0 +1,221 @@
+use crate::{height, node_count, permute_relation, swap_levels, Ldd, Storage};
+
+/// Returns a permutation of the levels, as defined by [crate::permute], that
+/// reduces the total number of nodes of the given sets and relations, see
+/// [node_count]. Every relation is given together with its meta, and the
+/// relations are permuted by [permute_relation]. Requires that all sets have
+/// the same height, which is the length of the permutation.
+///
+/// The permutation is computed by sifting \[Rudell93\], where every level in
+/// turn is moved to the position with the fewest nodes while all other levels
+/// keep their relative order.
+pub fn sifting(storage: &mut Storage, sets: &[Ldd], relations: &[(Ldd, Ldd)]) -> Vec<usize> {
+    let length = sets.iter().map(|set| height(storage, set) as usize).max().unwrap_or(0);
+
+    let mut reordering = Reordering {
+        sets: sets.to_vec(),
+        relations: relations.to_vec(),
+        order: (0..length).collect(),
+    };
+    let mut best_count = reordering.node_count(storage);
+
+    for level in 0..length {
+        let mut position = reordering.order.iter().position(|x| *x == level).unwrap();
+        let mut best_position = position;
+
+        // Move the level to the last position, then to the first position and finally back to the best position.
+        while position + 1 < length {
+            reordering.swap(storage, position);
+            position += 1;
+
+            let count = reordering.node_count(storage);
+            if count < best_count {
+                best_count = count;
+                best_position = position;
+            }
+        }
+
+        while position > 0 {
+            reordering.swap(storage, position - 1);
+            position -= 1;
+
+            let count = reordering.node_count(storage);
+            if count < best_count {
+                best_count = count;
+                best_position = position;
+            }
+        }
+
+        while position < best_position {
+            reordering.swap(storage, position);
+            position += 1;
+        }
+    }
+
+    reordering.order
+}
+
+/// Returns a permutation of the levels, as defined by [crate::permute], that
+/// places the levels used by the same group close together, where every group
+/// consists of the levels that are read or written by a transition group.
+///
+/// The permutation is computed by the FORCE heuristic \[Aloul03\], which
+/// repeatedly moves every level to the average center of gravity of its groups
+/// for as long as the total span of the groups decreases.
+pub fn force(length: usize, groups: &[Vec<usize>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..length).collect();
+    let mut best_span = span(&order, groups);
+
+    loop {
+        let mut position = vec![0.0; length];
+        for (i, level) in order.iter().enumerate() {
+            position[*level] = i as f64;
+        }
+
+        // The sum and number of the centers of gravity of the groups of every level.
+        let mut forces = vec![(0.0, 0); length];
+        for group in groups.iter().filter(|group| !group.is_empty()) {
+            let center = group.iter().map(|level| position[*level]).sum::<f64>() / group.len() as f64;
+            for level in group {
+                forces[*level].0 += center;
+                forces[*level].1 += 1;
+            }
+        }
+
+        // Levels without groups keep their current position.
+        let target: Vec<f64> = forces
+            .iter()
+            .enumerate()
+            .map(|(level, (sum, count))| if *count == 0 { position[level] } else { sum / *count as f64 })
+            .collect();
+
+        let mut next = order.clone();
+        next.sort_by(|a, b| target[*a].partial_cmp(&target[*b]).unwrap().then(a.cmp(b)));
+
+        let next_span = span(&next, groups);
+        if next_span >= best_span {
+            break;
+        }
+
+        order = next;
+        best_span = next_span;
+    }
+
+    order
+}
+
+/// Returns the sum over all groups of the distance between the first and last position of its levels in the given order.
+fn span(order: &[usize], groups: &[Vec<usize>]) -> usize {
+    let mut position = vec![0; order.len()];
+    for (i, level) in order.iter().enumerate() {
+        position[*level] = i;
+    }
+
+    groups
+        .iter()
+        .filter(|group| !group.is_empty())
+        .map(|group| {
+            let first = group.iter().map(|level| position[*level]).min().unwrap();
+            let last = group.iter().map(|level| position[*level]).max().unwrap();
+            last - first
+        })
+        .sum()
+}
+
+/// The sets and relations under the current order of the levels.
+struct Reordering {
+    sets: Vec<Ldd>,
+    relations: Vec<(Ldd, Ldd)>,
+    order: Vec<usize>, // The original level at every position.
+}
+
+impl Reordering {
+    /// Swaps the levels at the given position and the next position.
+    fn swap(&mut self, storage: &mut Storage, position: usize) {
+        for set in self.sets.iter_mut() {
+            *set = swap_levels(storage, set, position);
+        }
+
+        let mut transposition: Vec<usize> = (0..self.order.len()).collect();
+        transposition.swap(position, position + 1);
+        for (rel, meta) in self.relations.iter_mut() {
+            (*rel, *meta) = permute_relation(storage, rel, meta, &transposition);
+        }
+
+        self.order.swap(position, position + 1);
+    }
+
+    /// Returns the total number of nodes of the sets and relations.
+    fn node_count(&self, storage: &Storage) -> usize {
+        let mut roots = self.sets.clone();
+        roots.extend(self.relations.iter().map(|(rel, _)| rel.clone()));
+        node_count(storage, &roots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::*;
+    use crate::test_utility::*;
+
+    // Test that sifting reduces the number of nodes of a set where the first and second half are equal.
+    #[test]
+    fn test_sifting() {
+        let mut storage = Storage::new();
+
+        let set = from_iter(
+            &mut storage,
+            all_vectors(3, 4).iter().map(|x| [x.clone(), x.clone()].concat()).collect::<Vec<_>>().iter(),
+        );
+
+        let permutation = sifting(&mut storage, std::slice::from_ref(&set), &[]);
+        let result = permute(&mut storage, &set, &permutation);
+        assert!(
+            node_count(&storage, std::slice::from_ref(&result)) < node_count(&storage, std::slice::from_ref(&set)),
+            "sifting should reduce the number of nodes."
+        );
+    }
+
+    // Test that FORCE places levels of the same group next to each other.
+    #[test]
+    fn test_force() {
+        let groups = vec![vec![0, 3], vec![1, 4], vec![2, 5]];
+
+        let permutation = force(6, &groups);
+        assert_eq!(span(&permutation, &groups), 3, "every group should have adjacent levels in {:?}.", permutation);
+    }
+
+    // Test that FORCE returns a permutation that does not increase the span of random groups.
+    #[test]
+    fn random_force() {
+        let groups: Vec<Vec<usize>> = (0..10)
+            .map(|_| random_sorted_vector(3, 12).iter().map(|level| *level as usize).collect())
+            .collect();
+
+        let permutation = force(12, &groups);
+        let mut sorted = permutation.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..12).collect::<Vec<_>>(), "force should return a permutation.");
+        assert!(span(&permutation, &groups) <= span(&sorted, &groups));
+    }
+
+    // Test that sifting a random set and relation does not increase the number of nodes.
+    #[test]
+    fn random_sifting() {
+        let mut storage = Storage::new();
+
+        let set = from_iter(&mut storage, random_vector_set(32, 6, 4).iter());
+        let rel = from_iter(&mut storage, random_vector_set(32, 5, 4).iter());
+        let meta = compute_meta(&mut storage, &[0, 2, 3], &[3, 5]);
+
+        let permutation = sifting(&mut storage, std::slice::from_ref(&set), &[(rel.clone(), meta.clone())]);
+        let mut sorted = permutation.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..6).collect::<Vec<_>>(), "sifting should return a permutation.");
+
+        let result = permute(&mut storage, &set, &permutation);
+        let (result_rel, _) = permute_relation(&mut storage, &rel, &meta, &permutation);
+        assert!(node_count(&storage, &[result, result_rel]) <= node_count(&storage, &[set, rel]));
+    }
+}
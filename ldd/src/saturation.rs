@@ -0,0 +1,146 @@
+use crate::{cache_indexed_op, relational_product, union, DataRef, IndexedOperator, Ldd, LddRef, Storage};
+
+/// A transition group where meta starts at the top level of the group, i.e.,
+/// the first level that is read or written.
+struct Group {
+    top: usize,
+    relation: Ldd,
+    meta: Ldd,
+}
+
+/// Computes the set of vectors reachable from the initial set by means of
+/// saturation \[Ciardo01\], where every transition group is given by a sparse
+/// relation and meta = compute_meta(read_proj, write_proj) as for
+/// [relational_product](crate::relational_product).
+///
+/// # Details
+///
+/// The transition groups are sorted by their top level, which is the first
+/// level that is read or written by the group. The set is then saturated
+/// bottom-up: the groups at a given level are applied until a fixpoint is
+/// reached, where the nodes below that level are saturated with the groups
+/// that start below it first. The saturated results are stored in the
+/// operation cache, keyed on the node and the index of the first group.
+pub fn saturation(storage: &mut Storage, initial: &LddRef, groups: &[(Ldd, Ldd)]) -> Ldd {
+    // Determine the top level of every group, the meta is skipped to that level.
+    let mut sorted: Vec<Group> = Vec::new();
+    for (relation, meta) in groups {
+        let mut top = 0;
+        let mut meta = meta.clone();
+        while meta != *storage.empty_vector() && storage.value(&meta) == 0 {
+            meta = storage.down(&meta);
+            top += 1;
+        }
+
+        if meta != *storage.empty_vector() {
+            sorted.push(Group {
+                top,
+                relation: relation.clone(),
+                meta,
+            });
+        }
+        // Otherwise, the group does not read or write anything and can be ignored.
+    }
+    sorted.sort_by_key(|group| group.top);
+
+    // The cached results refer to indices in the sorted groups.
+    storage
+        .operation_cache()
+        .clear_indexed(&IndexedOperator::Saturation);
+    saturate(storage, &sorted, initial, 0, 0)
+}
+
+/// Saturates the given set at the given depth with the groups starting from
+/// index, which is the first group whose top level is at least the depth.
+fn saturate(storage: &mut Storage, groups: &[Group], set: &LddRef, index: usize, depth: usize) -> Ldd {
+    if set == storage.empty_set() {
+        storage.empty_set().clone()
+    } else if index == groups.len() || set == storage.empty_vector() {
+        storage.protect(set)
+    } else {
+        // The depth is determined by the index of the first group, so it is not part of the key.
+        cache_indexed_op(storage, IndexedOperator::Saturation, set, index, |storage, set, index| {
+            if groups[index].top > depth {
+                // There are no groups at this level, so saturate all nodes below.
+                let DataRef(value, down, right) = storage.get_ref(set);
+
+                let down_result = saturate(storage, groups, &down, index, depth + 1);
+                let right_result = saturate(storage, groups, &right, index, depth);
+                storage.insert(value, &down_result, &right_result)
+            } else {
+                // Apply the groups at this level until a fixpoint is reached, where the groups below are applied first.
+                let end = index
+                    + groups[index..]
+                        .iter()
+                        .take_while(|group| group.top == depth)
+                        .count();
+
+                let mut result = storage.protect(set);
+                loop {
+                    let previous = result.clone();
+
+                    result = saturate(storage, groups, &result, end, depth);
+                    for group in &groups[index..end] {
+                        let successors = relational_product(storage, &result, &group.relation, &group.meta);
+                        result = union(storage, &result, &successors);
+                    }
+
+                    if result == previous {
+                        break;
+                    }
+                }
+
+                result
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::*;
+    use crate::test_utility::*;
+
+    // Compare saturation with a breadth-first search for random transition groups.
+    #[test]
+    fn random_saturation() {
+        let mut storage = Storage::new();
+
+        let initial = from_iter(&mut storage, random_vector_set(4, 8, 4).iter());
+
+        let mut groups: Vec<(Ldd, Ldd)> = Vec::new();
+        for _ in 0..4 {
+            let read_proj = random_sorted_vector(2, 7);
+            let write_proj = random_sorted_vector(2, 7);
+
+            let meta = compute_meta(&mut storage, &read_proj, &write_proj);
+            let relation = from_iter(
+                &mut storage,
+                random_vector_set(16, read_proj.len() + write_proj.len(), 4).iter(),
+            );
+            groups.push((relation, meta));
+        }
+
+        // Compute the reachable states by a naive fixpoint computation.
+        let mut expected = initial.clone();
+        loop {
+            let previous = expected.clone();
+            for (relation, meta) in &groups {
+                let successors = relational_product(&mut storage, &expected, relation, meta);
+                expected = union(&mut storage, &expected, &successors);
+            }
+
+            if expected == previous {
+                break;
+            }
+        }
+
+        let result = saturation(&mut storage, &initial, &groups);
+        print_differences(&storage, &result, &expected);
+        assert_eq!(
+            result, expected,
+            "saturation should compute the same reachable states."
+        );
+    }
+}
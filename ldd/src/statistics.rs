@@ -0,0 +1,139 @@
+use crate::{DataRef, Ldd, LddRef, Storage};
+
+use std::collections::HashSet;
+
+/// The statistics of a single level of an LDD as computed by [level_profile].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LevelProfile {
+    /// The number of distinct nodes at this level.
+    pub nodes: usize,
+    /// The number of distinct right chains at this level, i.e., the number of
+    /// distinct down nodes of the level above.
+    pub chains: usize,
+    /// The length of the longest right chain at this level.
+    pub max_chain_length: usize,
+}
+
+/// Returns the number of distinct nodes that are reachable from the given
+/// roots, where nodes shared between roots are counted once. The 'true' and
+/// 'false' nodes are not counted.
+pub fn node_count(storage: &Storage, roots: &[Ldd]) -> usize {
+    // The nodes are reachable from the roots, so they are visited by their index without protecting them.
+    let mut marked: HashSet<usize> = HashSet::new();
+    let mut stack: Vec<usize> = roots.iter().map(|root| root.index()).collect();
+
+    while let Some(current) = stack.pop() {
+        if is_terminal(storage, current) || !marked.insert(current) {
+            continue;
+        }
+
+        let node = LddRef::new(current);
+        let DataRef(_, down, right) = storage.get_ref(&node);
+        stack.push(down.index());
+        stack.push(right.index());
+    }
+
+    marked.len()
+}
+
+/// Returns the [LevelProfile] of every level of the given roots, where the
+/// first element is the top level. Requires that all roots have the same
+/// height, and nodes shared between roots are counted once.
+pub fn level_profile(storage: &Storage, roots: &[Ldd]) -> Vec<LevelProfile> {
+    let mut result = Vec::new();
+
+    // The indices of the distinct heads of the right chains at the current level.
+    let mut heads: HashSet<usize> = roots
+        .iter()
+        .map(|root| root.index())
+        .filter(|root| !is_terminal(storage, *root))
+        .collect();
+
+    while !heads.is_empty() {
+        let mut profile = LevelProfile {
+            chains: heads.len(),
+            ..Default::default()
+        };
+
+        let mut marked: HashSet<usize> = HashSet::new();
+        let mut next: HashSet<usize> = HashSet::new();
+        for head in &heads {
+            let mut length = 0;
+            let mut current = *head;
+            while current != storage.empty_set().index() {
+                debug_assert_ne!(current, storage.empty_vector().index(), "all roots should have the same height");
+
+                let node = LddRef::new(current);
+                let DataRef(_, down, right) = storage.get_ref(&node);
+                marked.insert(current);
+                if down != *storage.empty_vector() {
+                    next.insert(down.index());
+                }
+
+                length += 1;
+                current = right.index();
+            }
+
+            profile.max_chain_length = profile.max_chain_length.max(length);
+        }
+
+        profile.nodes = marked.len();
+        result.push(profile);
+        heads = next;
+    }
+
+    result
+}
+
+/// Returns true iff the node with the given index is 'true' or 'false'.
+fn is_terminal(storage: &Storage, index: usize) -> bool {
+    index == storage.empty_set().index() || index == storage.empty_vector().index()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::*;
+    use crate::test_utility::*;
+
+    // Test the node_count and level_profile functions on a small set.
+    #[test]
+    fn test_node_count() {
+        let mut storage = Storage::new();
+
+        // The set { <0, 0>, <0, 1>, <1, 0>, <1, 1> } shares the chain at the second level.
+        let set = from_iter(&mut storage, [vec![0, 0], vec![0, 1], vec![1, 0], vec![1, 1]].iter());
+        let single = singleton(&mut storage, &[1, 1]);
+
+        assert_eq!(node_count(&storage, std::slice::from_ref(&set)), 4);
+        assert_eq!(node_count(&storage, std::slice::from_ref(&single)), 2);
+        assert_eq!(node_count(&storage, &[set.clone(), set.clone()]), 4);
+        assert_eq!(node_count(&storage, &[set.clone(), single.clone()]), 5);
+        assert_eq!(node_count(&storage, &[storage.empty_set().clone()]), 0);
+
+        let profile = level_profile(&storage, &[set, single]);
+        assert_eq!(
+            profile,
+            vec![
+                LevelProfile { nodes: 3, chains: 2, max_chain_length: 2 },
+                LevelProfile { nodes: 2, chains: 2, max_chain_length: 2 },
+            ]
+        );
+    }
+
+    // Test that the level profile is consistent with the node count for random inputs.
+    #[test]
+    fn random_level_profile() {
+        let mut storage = Storage::new();
+
+        let a = from_iter(&mut storage, random_vector_set(32, 10, 10).iter());
+        let b = from_iter(&mut storage, random_vector_set(32, 10, 10).iter());
+
+        let profile = level_profile(&storage, &[a.clone(), b.clone()]);
+        assert_eq!(profile.len(), 10);
+        assert_eq!(
+            profile.iter().map(|level| level.nodes).sum::<usize>(),
+            node_count(&storage, &[a, b])
+        );
+    }
+}
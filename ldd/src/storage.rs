@@ -16,6 +16,12 @@ use self::protection_set::ProtectionSet;
 
 pub type Value = u32;
 
+/// The reserved value of a copy node in a relation, which indicates that the
+/// value that was read is written. Since it is the largest value a copy node
+/// is always the last node in its right chain, and the standard set
+/// operations treat it as any other value.
+pub const COPY: Value = Value::MAX;
+
 /// This is the LDD node(value, down, right) with some additional meta data.
 #[derive(Clone)]
 pub struct Node
@@ -99,8 +105,8 @@ impl Storage
         let shared = Rc::new(RefCell::new(ProtectionSet::new()));
         // Add two nodes representing 'false' and 'true' respectively; these cannot be created using insert.
         let mut nodes = IndexedSet::new();
-        let empty_set = nodes.insert(Node::new(0, 0, 0));
-        let empty_vector = nodes.insert(Node::new(1, 0, 0));
+        let (empty_set, _) = nodes.insert(Node::new(0, 0, 0));
+        let (empty_vector, _) = nodes.insert(Node::new(1, 0, 0));
 
         Self { 
             protection_set: shared.clone(),
@@ -146,11 +152,22 @@ impl Storage
             self.count_until_collection = self.nodes.len() as u64;
         }
         
-        let index = self.nodes.insert(Node::new(value, down.index(), right.index()));
+        let (index, inserted) = self.nodes.insert(Node::new(value, down.index(), right.index()));
+        if inserted
+        {
+            self.count_until_collection -= 1;
+        }
                
         Ldd::new(&self.protection_set, index)
     }
 
+    /// Create a new LDD node(copy, down, 'false'), see [COPY].
+    pub fn insert_copy(&mut self, down: &LddRef) -> Ldd
+    {
+        let empty_set = self.empty_set.clone();
+        self.insert(COPY, down, &empty_set)
+    }
+
     /// Upgrade an [LddRef] to a protected [Ldd] instance.
     pub fn protect(&mut self, ldd: &LddRef) -> Ldd
     {
@@ -355,4 +372,32 @@ mod tests
 
         storage.garbage_collect();
     }
+
+    #[test]
+    fn test_automatic_garbage_collection()
+    {
+        let mut storage = Storage::new();
+        let empty_set = storage.empty_set().clone();
+        let empty_vector = storage.empty_vector().clone();
+
+        // Inserting nodes that already exist does not count towards the next garbage collection.
+        let vector = random_vector(10, 10);
+        let ldd = singleton(&mut storage, &vector);
+        let count = storage.count_until_collection;
+        let _same = singleton(&mut storage, &vector);
+        assert_eq!(storage.count_until_collection, count, "Existing nodes should not count towards the next garbage collection.");
+
+        // Create unprotected nodes until the next insertion triggers a garbage collection.
+        let mut value = 0;
+        while storage.count_until_collection > 0
+        {
+            storage.insert(value, &empty_vector, &empty_set);
+            value += 1;
+        }
+
+        let before = storage.nodes.iter().count();
+        let _last = storage.insert(value, &empty_vector, &empty_set);
+        assert!(storage.nodes.iter().count() < before, "The unprotected nodes should have been collected.");
+        assert_eq!(singleton(&mut storage, &vector), ldd, "The protected nodes should not have been collected.");
+    }
 }
@@ -13,13 +13,21 @@ use super::protection_set::ProtectionSet;
 /// 
 /// For all operations defined in `operations.rs` where caching helps we
 /// introduce a cache. The cache that belongs to one operation is identified by
-/// the value of [UnaryFunction], [BinaryOperator] or [TernaryOperator].
+/// the value of [UnaryFunction], [UnaryFunctionU128], [UnaryFunctionF64],
+/// [BinaryPredicate], [BinaryOperator], [BinaryPairOperator],
+/// [TernaryOperator], [QuaternaryOperator] or [IndexedOperator].
 pub struct OperationCache
 {
     protection_set: Rc<RefCell<ProtectionSet<usize>>>,
     caches1: Vec<Cache<usize, usize>>,
+    caches1_u128: Vec<Cache<usize, Option<u128>>>,
+    caches1_f64: Vec<Cache<usize, f64>>,
+    caches2_bool: Vec<Cache<(usize, usize), bool>>,
     caches2: Vec<Cache<(usize, usize), usize>>,
+    caches2_pair: Vec<Cache<(usize, usize), (usize, usize)>>,
     caches3: Vec<Cache<(usize, usize, usize), usize>>,
+    caches4: Vec<Cache<(usize, usize, usize, usize), usize>>,
+    caches_indexed: Vec<Cache<(usize, usize), usize>>,
 }
 
 impl OperationCache
@@ -29,8 +37,14 @@ impl OperationCache
         OperationCache {
             protection_set,
             caches1: vec![Cache::new()],
-            caches2: vec![Cache::new(); 3],
-            caches3: vec![Cache::new()],
+            caches1_u128: vec![Cache::new()],
+            caches1_f64: vec![Cache::new()],
+            caches2_bool: vec![Cache::new(); 3],
+            caches2: vec![Cache::new(); 8],
+            caches2_pair: vec![Cache::new(); 2],
+            caches3: vec![Cache::new(); 3],
+            caches4: vec![Cache::new()],
+            caches_indexed: vec![Cache::new(); 6],
         }
     }
 
@@ -39,37 +53,22 @@ impl OperationCache
     /// protected.
     pub fn clear(&mut self)
     {    
-        for cache in self.caches1.iter_mut() {
-            cache.clear();
-        }
-
-        for cache in self.caches2.iter_mut() {
+        for cache in self.all_caches_mut() {
             cache.clear();
         }
+    }
 
-        for cache in self.caches3.iter_mut() {
-            cache.clear();
-        }    
+    /// Clear the cache of the given indexed operator. This must be done
+    /// whenever the context that the indices refer to changes.
+    pub fn clear_indexed(&mut self, operator: &IndexedOperator)
+    {
+        self.get_cache_indexed(operator).clear();
     }
 
     /// Returns the number of elements in the operation cache.
     pub fn len(&self) -> usize
     {
-        let mut result: usize = 0;
-        
-        for cache in self.caches1.iter() {
-            result += cache.len();
-        }
-
-        for cache in self.caches2.iter() {
-            result += cache.len();
-        }
-
-        for cache in self.caches3.iter() {
-            result += cache.len();
-        }
-        
-        result
+        self.all_caches().map(|cache| cache.len()).sum()
     }
 
     /// Returns true iff the operation cache is empty.
@@ -78,21 +77,54 @@ impl OperationCache
         self.len() == 0
     }
 
-    /// Puts a limit on the operation cache size. This will ensure that
-    /// self.len() <= n if self.limit(n) has been set.
+    /// Puts a limit on the operation cache size, which is divided equally over
+    /// the caches that have been used since the previous limit and rounded
+    /// down to a power of two. The other caches are reset to their initial
+    /// size, such that operations that are not used do not take memory from
+    /// the others.
     pub fn limit(&mut self, size: usize)
     {
-        for cache in self.caches1.iter_mut() {
-            cache.limit(size/4);
+        let used = self.all_caches().filter(|cache| cache.is_used()).count();
+        let cache_size = match size / used.max(1) {
+            0 => 1,
+            x => 1 << (usize::BITS - 1 - x.leading_zeros()),
+        };
+
+        for cache in self.all_caches_mut() {
+            if cache.is_used() {
+                cache.limit(cache_size);
+            } else {
+                cache.limit(INITIAL_CACHE_SIZE);
+            }
         }
+    }
 
-        for cache in self.caches2.iter_mut() {
-            cache.limit(size/4);
-        }
+    /// Returns an iterator over all the caches.
+    fn all_caches(&self) -> impl Iterator<Item = &dyn Limit>
+    {
+        self.caches1.iter().map(|cache| cache as &dyn Limit)
+            .chain(self.caches1_u128.iter().map(|cache| cache as &dyn Limit))
+            .chain(self.caches1_f64.iter().map(|cache| cache as &dyn Limit))
+            .chain(self.caches2_bool.iter().map(|cache| cache as &dyn Limit))
+            .chain(self.caches2.iter().map(|cache| cache as &dyn Limit))
+            .chain(self.caches2_pair.iter().map(|cache| cache as &dyn Limit))
+            .chain(self.caches3.iter().map(|cache| cache as &dyn Limit))
+            .chain(self.caches4.iter().map(|cache| cache as &dyn Limit))
+            .chain(self.caches_indexed.iter().map(|cache| cache as &dyn Limit))
+    }
 
-        for cache in self.caches3.iter_mut() {
-            cache.limit(size/4);
-        }   
+    /// Returns a mutable iterator over all the caches.
+    fn all_caches_mut(&mut self) -> impl Iterator<Item = &mut dyn Limit>
+    {
+        self.caches1.iter_mut().map(|cache| cache as &mut dyn Limit)
+            .chain(self.caches1_u128.iter_mut().map(|cache| cache as &mut dyn Limit))
+            .chain(self.caches1_f64.iter_mut().map(|cache| cache as &mut dyn Limit))
+            .chain(self.caches2_bool.iter_mut().map(|cache| cache as &mut dyn Limit))
+            .chain(self.caches2.iter_mut().map(|cache| cache as &mut dyn Limit))
+            .chain(self.caches2_pair.iter_mut().map(|cache| cache as &mut dyn Limit))
+            .chain(self.caches3.iter_mut().map(|cache| cache as &mut dyn Limit))
+            .chain(self.caches4.iter_mut().map(|cache| cache as &mut dyn Limit))
+            .chain(self.caches_indexed.iter_mut().map(|cache| cache as &mut dyn Limit))
     }
 
     fn get_cache1(&mut self, operator: &UnaryFunction) -> &mut Cache<usize, usize>
@@ -102,12 +134,48 @@ impl OperationCache
         }
     }
 
+    fn get_cache1_u128(&mut self, operator: &UnaryFunctionU128) -> &mut Cache<usize, Option<u128>>
+    {
+        match operator {
+            UnaryFunctionU128::Len => &mut self.caches1_u128[0],
+        }
+    }
+
+    fn get_cache1_f64(&mut self, operator: &UnaryFunctionF64) -> &mut Cache<usize, f64>
+    {
+        match operator {
+            UnaryFunctionF64::Log2Len => &mut self.caches1_f64[0],
+        }
+    }
+
+    fn get_cache2_bool(&mut self, operator: &BinaryPredicate) -> &mut Cache<(usize, usize), bool>
+    {
+        match operator {
+            BinaryPredicate::Subset => &mut self.caches2_bool[0],
+            BinaryPredicate::Disjoint => &mut self.caches2_bool[1],
+            BinaryPredicate::Invertible => &mut self.caches2_bool[2],
+        }
+    }
+
     fn get_cache2(&mut self, operator: &BinaryOperator) -> &mut Cache<(usize, usize), usize>
     {
         match operator {
             BinaryOperator::Union => &mut self.caches2[0],
             BinaryOperator::Merge => &mut self.caches2[1],
-            BinaryOperator::Minus => &mut self.caches2[2]
+            BinaryOperator::Minus => &mut self.caches2[2],
+            BinaryOperator::Intersect => &mut self.caches2[3],
+            BinaryOperator::Project => &mut self.caches2[4],
+            BinaryOperator::Compose => &mut self.caches2[5],
+            BinaryOperator::Inverse => &mut self.caches2[6],
+            BinaryOperator::Concat => &mut self.caches2[7],
+        }
+    }
+
+    fn get_cache2_pair(&mut self, operator: &BinaryPairOperator) -> &mut Cache<(usize, usize), (usize, usize)>
+    {
+        match operator {
+            BinaryPairOperator::Zip => &mut self.caches2_pair[0],
+            BinaryPairOperator::TransitiveClosure => &mut self.caches2_pair[1],
         }
     }
 
@@ -115,6 +183,27 @@ impl OperationCache
     {
         match operator {
             TernaryOperator::RelationalProduct => &mut self.caches3[0],
+            TernaryOperator::Match => &mut self.caches3[1],
+            TernaryOperator::ProjectMinus => &mut self.caches3[2],
+        }
+    }
+
+    fn get_cache4(&mut self, operator: &QuaternaryOperator) -> &mut Cache<(usize, usize, usize, usize), usize>
+    {
+        match operator {
+            QuaternaryOperator::RelationalPrev => &mut self.caches4[0],
+        }
+    }
+
+    fn get_cache_indexed(&mut self, operator: &IndexedOperator) -> &mut Cache<(usize, usize), usize>
+    {
+        match operator {
+            IndexedOperator::Saturation => &mut self.caches_indexed[0],
+            IndexedOperator::ExtendRelation => &mut self.caches_indexed[1],
+            IndexedOperator::InsertLevel => &mut self.caches_indexed[2],
+            IndexedOperator::RemoveLevel => &mut self.caches_indexed[3],
+            IndexedOperator::DuplicateLevel => &mut self.caches_indexed[4],
+            IndexedOperator::SwapLevels => &mut self.caches_indexed[5],
         }
     }
 
@@ -133,15 +222,20 @@ pub struct Cache<K, V, S = RandomState>
 {
     table: Vec<(K, V)>,
     hash_builder: S,
+    used: bool, // Whether an element has been inserted since the last limit.
 }
 
+/// The number of elements of a cache that has not been limited.
+const INITIAL_CACHE_SIZE: usize = 1024;
+
 impl<K: Default + Clone, V: Clone + Default> Cache<K, V, RandomState>
 {
     pub fn new() -> Cache<K, V, RandomState>
     {
         Cache {
-            table: vec![Default::default(); 1024],
+            table: vec![Default::default(); INITIAL_CACHE_SIZE],
             hash_builder: RandomState::default(),
+            used: false,
         }
     }
 }
@@ -171,6 +265,13 @@ impl<K: Default + Clone, V: Clone + Default, S> Cache<K, V, S>
 
         self.table.clear();
         self.table.resize(power_of_two, Default::default());
+        self.used = false;
+    }
+
+    /// Returns true iff an element has been inserted since the last limit.
+    pub fn is_used(&self) -> bool
+    {
+        self.used
     }
 
     /// Returns the amount of elements in the cache.
@@ -219,6 +320,7 @@ impl<K: Default + Eq + Hash, V, S: BuildHasher> Cache<K, V, S>
         key.hash(&mut hasher);
         let index = hasher.finish() % (self.table.len() as u64);
         self.table[index as usize] = (key, value);
+        self.used = true;
     }
 }
 
@@ -229,28 +331,116 @@ impl<K: Clone, V: Clone, S: Clone> Clone for Cache<K, V, S>
         Cache { 
             table: self.table.clone(), 
             hash_builder: self.hash_builder.clone(),
+            used: self.used,
         }
     }
 }
 
+/// The size related functions of a [Cache], independent of its key and value types.
+trait Limit
+{
+    fn clear(&mut self);
+
+    fn len(&self) -> usize;
+
+    fn is_used(&self) -> bool;
+
+    fn limit(&mut self, size: usize);
+}
+
+impl<K: Default + Clone, V: Clone + Default, S> Limit for Cache<K, V, S>
+{
+    fn clear(&mut self)
+    {
+        Cache::clear(self)
+    }
+
+    fn len(&self) -> usize
+    {
+        Cache::len(self)
+    }
+
+    fn is_used(&self) -> bool
+    {
+        Cache::is_used(self)
+    }
+
+    fn limit(&mut self, size: usize)
+    {
+        Cache::limit(self, size)
+    }
+}
+
 /// Any function from LDD -> usize.
 pub enum UnaryFunction
 {
     Len,
 }
 
+/// Any function from LDD -> Option<u128>.
+pub enum UnaryFunctionU128
+{
+    Len,
+}
+
+/// Any function from LDD -> f64.
+pub enum UnaryFunctionF64
+{
+    Log2Len,
+}
+
+/// Any predicate on LDD x LDD, i.e., a function LDD x LDD -> bool.
+pub enum BinaryPredicate
+{
+    Subset,
+    Disjoint,
+    Invertible,
+}
+
 /// Any operator from LDD x LDD -> LDD.
 pub enum BinaryOperator
 {
     Union,
     Merge,
     Minus,
+    Intersect,
+    Project,
+    Compose,
+    Inverse,
+    Concat,
+}
+
+/// Any operator from LDD x LDD -> LDD x LDD.
+pub enum BinaryPairOperator
+{
+    Zip,
+    TransitiveClosure,
 }
 
 /// Any operator from LDD x LDD x LDD -> LDD.
 pub enum TernaryOperator
 {
     RelationalProduct,
+    Match,
+    ProjectMinus,
+}
+
+/// Any operator from LDD x LDD x LDD x LDD -> LDD.
+pub enum QuaternaryOperator
+{
+    RelationalPrev,
+}
+
+/// Any operator from LDD x usize -> LDD, where the index refers to a context
+/// that remains fixed while the operator is evaluated.
+pub enum IndexedOperator
+{
+    Saturation,
+    ExtendRelation,
+    InsertLevel,
+    RemoveLevel,
+    DuplicateLevel,
+    SwapLevels,
 }
 
 /// Implements an operation cache for a unary LDD operator.
@@ -270,6 +460,70 @@ pub fn cache_unary_function<F>(storage: &mut Storage, operator: UnaryFunction, a
     }
 }
 
+/// Implements an operation cache for a unary LDD function with an Option<u128> result.
+pub fn cache_unary_function_u128<F>(storage: &mut Storage, operator: UnaryFunctionU128, a: &LddRef, f: F) -> Option<u128>
+    where F: Fn(&mut Storage, &LddRef) -> Option<u128>
+{
+    let key = a.index();
+    if let Some(result) = storage.operation_cache().get_cache1_u128(&operator).get(&key) 
+    {
+        *result
+    }
+    else 
+    {
+        let result = f(storage,  a);
+        storage.operation_cache().get_cache1_u128(&operator).insert(key, result);
+        result
+    }
+}
+
+/// Implements an operation cache for a unary LDD function with a f64 result.
+pub fn cache_unary_function_f64<F>(storage: &mut Storage, operator: UnaryFunctionF64, a: &LddRef, f: F) -> f64
+    where F: Fn(&mut Storage, &LddRef) -> f64
+{
+    let key = a.index();
+    if let Some(result) = storage.operation_cache().get_cache1_f64(&operator).get(&key) 
+    {
+        *result
+    }
+    else 
+    {
+        let result = f(storage,  a);
+        storage.operation_cache().get_cache1_f64(&operator).insert(key, result);
+        result
+    }
+}
+
+/// Implements an operation cache for a binary LDD predicate.
+pub fn cache_binary_predicate<F>(storage: &mut Storage, operator: BinaryPredicate, a: &LddRef, b: &LddRef, f: F) -> bool
+    where F: Fn(&mut Storage, &LddRef, &LddRef) -> bool
+{
+    let key = (a.index(), b.index());
+    if let Some(result) = storage.operation_cache().get_cache2_bool(&operator).get(&key) 
+    {
+        *result
+    }
+    else 
+    {
+        let result = f(storage,  a, b);
+        storage.operation_cache().get_cache2_bool(&operator).insert(key, result);
+        result
+    }
+}
+
+/// Implements an operation cache for a symmetric binary LDD predicate, i.e., a
+/// predicate p such that p(a,b) = p(b,a) for all LDD a and b.
+pub fn cache_comm_binary_predicate<F>(storage: &mut Storage, operator: BinaryPredicate, a: &LddRef, b: &LddRef, f: F) -> bool
+    where F: Fn(&mut Storage, &LddRef, &LddRef) -> bool
+{
+    // Reorder the inputs to improve caching behaviour (can potentially half the cache size)
+    if a.index() < b.index() {
+        cache_binary_predicate(storage, operator, a, b, f)
+    } else {
+        cache_binary_predicate(storage, operator, b, a, f)
+    }
+}
+
 /// Implements an operation cache for a binary LDD operator.
 pub fn cache_binary_op<F>(storage: &mut Storage, operator: BinaryOperator, a: &LddRef, b: &LddRef, f: F) -> Ldd
     where F: Fn(&mut Storage, &LddRef, &LddRef) -> Ldd
@@ -301,6 +555,24 @@ pub fn cache_comm_binary_op<F>(storage: &mut Storage, operator: BinaryOperator,
     }
 }
 
+/// Implements an operation cache for a binary LDD operator with two results.
+pub fn cache_binary_pair_op<F>(storage: &mut Storage, operator: BinaryPairOperator, a: &LddRef, b: &LddRef, f: F) -> (Ldd, Ldd)
+    where F: Fn(&mut Storage, &LddRef, &LddRef) -> (Ldd, Ldd)
+{
+    let key = (a.index(), b.index());
+    if let Some(result) = storage.operation_cache().get_cache2_pair(&operator).get(&key) 
+    {
+        let (first, second) = *result; // Necessary to decouple borrow from storage and the call to create.
+        (storage.operation_cache().create(first), storage.operation_cache().create(second))
+    }
+    else 
+    {
+        let (first, second) = f(storage,  a, b);
+        storage.operation_cache().get_cache2_pair(&operator).insert(key, (first.index(), second.index()));
+        (first, second)
+    }
+}
+
 /// Implements an operation cache for a terniary LDD operator.
 pub fn cache_terniary_op<F>(storage: &mut Storage, operator: TernaryOperator, a: &LddRef, b: &LddRef, c: &LddRef, f: F) -> Ldd
     where F: Fn(&mut Storage, &LddRef, &LddRef, &LddRef) -> Ldd
@@ -318,3 +590,69 @@ pub fn cache_terniary_op<F>(storage: &mut Storage, operator: TernaryOperator, a:
         result
     }
 }
+
+/// Implements an operation cache for a quaternary LDD operator.
+pub fn cache_quaternary_op<F>(storage: &mut Storage, operator: QuaternaryOperator, a: &LddRef, b: &LddRef, c: &LddRef, d: &LddRef, f: F) -> Ldd
+    where F: Fn(&mut Storage, &LddRef, &LddRef, &LddRef, &LddRef) -> Ldd
+{
+    let key = (a.index(), b.index(), c.index(), d.index());
+    if let Some(result) = storage.operation_cache().get_cache4(&operator).get(&key) 
+    {
+        let result = *result; // Necessary to decouple borrow from storage and the call to create.
+        storage.operation_cache().create(result)
+    }
+    else 
+    {
+        let result = f(storage,  a, b, c, d);
+        storage.operation_cache().get_cache4(&operator).insert(key, result.index());
+        result
+    }
+}
+
+/// Implements an operation cache for an indexed LDD operator.
+pub fn cache_indexed_op<F>(storage: &mut Storage, operator: IndexedOperator, a: &LddRef, index: usize, f: F) -> Ldd
+    where F: Fn(&mut Storage, &LddRef, usize) -> Ldd
+{
+    let key = (a.index(), index);
+    if let Some(result) = storage.operation_cache().get_cache_indexed(&operator).get(&key) 
+    {
+        let result = *result; // Necessary to decouple borrow from storage and the call to create.
+        storage.operation_cache().create(result)
+    }
+    else 
+    {
+        let result = f(storage,  a, index);
+        storage.operation_cache().get_cache_indexed(&operator).insert(key, result.index());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn test_operation_cache_limit()
+    {
+        let mut cache = OperationCache::new(Rc::new(RefCell::new(ProtectionSet::new())));
+
+        for size in [0, 1, 100, 1000, 1 << 16]
+        {
+            // Only the used caches share the given size.
+            cache.get_cache2(&BinaryOperator::Union).insert((2, 3), 4);
+            cache.get_cache3(&TernaryOperator::RelationalProduct).insert((2, 3, 4), 5);
+            cache.limit(size);
+
+            let limit = (size / 2).max(1);
+            let limit = 1 << (usize::BITS - 1 - limit.leading_zeros());
+            assert_eq!(cache.get_cache2(&BinaryOperator::Union).len(), limit);
+            assert_eq!(cache.get_cache3(&TernaryOperator::RelationalProduct).len(), limit);
+            assert_eq!(cache.len(), 2 * limit + (cache.all_caches().count() - 2) * INITIAL_CACHE_SIZE);
+
+            // Afterwards, none of the caches have been used.
+            cache.limit(size);
+            assert_eq!(cache.len(), cache.all_caches().count() * INITIAL_CACHE_SIZE);
+        }
+    }
+}
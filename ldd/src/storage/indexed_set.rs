@@ -60,14 +60,14 @@ impl<T> IndexedSet<T> {
         self.table.capacity()
     }
 
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             reference: self,
             index: 0
         }
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         let iter = self.table.iter_mut().filter(|element| {
                 matches!(element, Entry::Filled(_))
             }).map(|element| {
@@ -83,11 +83,14 @@ impl<T> IndexedSet<T> {
 
 impl<T: Eq + Hash + Clone> IndexedSet<T> {
     
-    /// Inserts the given element into the set, and returns the corresponding index.
-    pub fn insert(&mut self, value: T) -> usize {
-        *self.index.entry(value.clone()).or_insert_with(
+    /// Inserts the given element into the set, and returns the corresponding
+    /// index and whether the element was not yet present in the set.
+    pub fn insert(&mut self, value: T) -> (usize, bool) {
+        let mut inserted = false;
+        let index = *self.index.entry(value.clone()).or_insert_with(
             || 
             {
+                inserted = true;
                 match self.free {
                     Some(first) =>
                     {
@@ -114,7 +117,9 @@ impl<T: Eq + Hash + Clone> IndexedSet<T> {
                         self.table.len() - 1
                     }
                 }
-            })
+            });
+
+        (index, inserted)
     }
 
     /// Erases all elements for which f(index, element) returns false. Allows
@@ -246,9 +251,10 @@ mod tests {
         // Insert several elements and keep track of the resulting indices.
         let mut set: IndexedSet::<usize> = IndexedSet::default();
         for element in &input {
-            let index = set.insert(*element);
+            let (index, inserted) = set.insert(*element);
+            assert_eq!(inserted, !indices.contains_key(element), "Only a new element should be inserted");
 
-            assert!(indices.get(&index).is_none() || indices.get(&index).unwrap() == element, "Index was already used for another element");
+            assert!(!indices.contains_key(&index) || indices.get(&index).unwrap() == element, "Index was already used for another element");
             indices.insert(*element, index);
         }
 
@@ -1,10 +1,11 @@
-use crate::{Ldd, Storage, operations::*, Value, iterators::*};
+//! Functions in this module are only relevant for testing purposes.
 
+use crate::{Ldd, Storage, operations::*, Value, iterators::*, COPY};
+
+use std::cmp;
 use std::collections::HashSet;
 use rand::Rng;
 
-///! Functions in this module are only relevant for testing purposes.
-
 /// Returns a vector of the given length with random u64 values (from 0..max_value).
 pub fn random_vector(length: usize, max_value: Value) -> Vec<Value> 
 {
@@ -29,6 +30,27 @@ pub fn random_sorted_vector(length: usize, max_value: Value) -> Vec<Value>
     result
 }
 
+/// Returns all vectors of the given length with values from 0..max_value.
+pub fn all_vectors(length: usize, max_value: Value) -> Vec<Vec<Value>>
+{
+    let mut result = vec![Vec::new()];
+    for _ in 0..length
+    {
+        result = result
+            .into_iter()
+            .flat_map(|vector| {
+                (0..max_value).map(move |value| {
+                    let mut vector = vector.clone();
+                    vector.push(value);
+                    vector
+                })
+            })
+            .collect();
+    }
+
+    result
+}
+
 /// Returns a set of 'amount' vectors where every vector has the given length.
 pub fn random_vector_set(amount: usize, length: usize, max_value: Value) ->  HashSet<Vec<Value>>
 {
@@ -94,4 +116,77 @@ pub fn project_vector(vector: &[Value], proj: &[Value]) -> Vec<Value>
         result.push(vector[*i as usize]);
     }
     result
-}
\ No newline at end of file
+}
+
+/// Returns the indices of the read and written values in the vectors of a relation for the given projections.
+pub fn relation_layout(read_proj: &[Value], write_proj: &[Value]) -> (Vec<Value>, Vec<Value>)
+{
+    let mut read_rel_proj = Vec::new();
+    let mut write_rel_proj = Vec::new();
+
+    let mut index = 0;
+    for i in 0..=cmp::max(read_proj.iter().max(), write_proj.iter().max()).copied().unwrap_or(0)
+    {
+        if read_proj.contains(&i)
+        {
+            read_rel_proj.push(index);
+            index += 1;
+        }
+        if write_proj.contains(&i)
+        {
+            write_rel_proj.push(index);
+            index += 1;
+        }
+    }
+
+    (read_rel_proj, write_rel_proj)
+}
+
+/// Returns a random relation of 'amount' vectors for the given projections, where the written values are replaced by copies with probability 0.5.
+pub fn random_copy_relation(amount: usize, read_proj: &[Value], write_proj: &[Value], max_value: Value) -> HashSet<Vec<Value>>
+{
+    let length = read_proj.len() + write_proj.len();
+    let write_rel_proj = relation_layout(read_proj, write_proj).1;
+
+    random_vector_set(amount, length, max_value)
+        .into_iter()
+        .map(|mut vector| {
+            for i in &write_rel_proj
+            {
+                if rand::thread_rng().gen_bool(0.5)
+                {
+                    vector[*i as usize] = COPY;
+                }
+            }
+            vector
+        })
+        .collect()
+}
+
+/// Computes x[write_proj := y'] for every (x', y') in the relation such that project(x, read_proj) = x', where COPY in x' matches any value and COPY in y' writes the value that was read, see [relational_product].
+pub fn explicit_successors(x: &[Value], relation: &HashSet<Vec<Value>>, read_proj: &[Value], write_proj: &[Value]) -> HashSet<Vec<Value>>
+{
+    let (read_rel_proj, write_rel_proj) = relation_layout(read_proj, write_proj);
+
+    let mut result = HashSet::new();
+    for rel in relation.iter()
+    {
+        if project_vector(x, read_proj)
+            .iter()
+            .zip(project_vector(rel, &read_rel_proj))
+            .all(|(x, r)| r == COPY || *x == r)
+        {
+            let mut value = x.to_vec();
+            for (w, y) in write_proj.iter().zip(project_vector(rel, &write_rel_proj))
+            {
+                if y != COPY
+                {
+                    value[*w as usize] = y;
+                }
+            }
+            result.insert(value);
+        }
+    }
+
+    result
+}